@@ -1,20 +1,61 @@
 use crate::{digit::*, Index, IndexError, Cutoff, cutoff::*};
 
-use std::{fmt, ops as stdops, iter::{repeat}, cmp::{max, min}, convert::From, slice::{IterMut}};
+pub use parse::{ParseError, MAX_PARSE_BITS};
+pub use convert::{FloatError, IntError};
+pub use radix::RadixDigits;
+pub use bytes::BytesError;
+pub use array::{ArrayBigFixed, ArrayError};
+#[cfg(feature = "codec")]
+pub use scale::{BoundedBigFixed, BoundedCutoffBoundBigFixed};
+
+use smallvec::SmallVec;
+
+use core::{fmt, ops as stdops, iter::{repeat_n}, cmp::{max, min}, convert::From, slice::{IterMut}};
+
+use alloc::{string::String, borrow::Cow};
 
 pub mod index_ops;
 pub mod convert;
 pub mod ops;
 pub mod ops_c;
 pub mod exp;
+pub mod parse;
+pub(crate) mod magnitude;
+pub mod display;
+pub mod radix;
+pub mod ratio;
+pub mod bytes;
+pub mod array;
+pub mod sum;
+#[cfg(feature = "serde")]
+pub mod serialize;
+#[cfg(feature = "codec")]
+pub mod scale;
+#[cfg(feature = "num-traits")]
+pub mod num;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum BigFixedError {
-    IndexError(IndexError)
+    IndexError(IndexError),
+    ParseError(ParseError),
+    FloatError(FloatError),
+    IntError(IntError),
+    BytesError(BytesError),
+    ArrayError(ArrayError),
+    DivisionByZero,
+    // an exact quotient was asked for but it has no terminating binary expansion
+    NonTerminating,
+    // a sum or product of CutoffBoundBigFixeds over an empty iterator, which has no scheme to bind the result to
+    EmptyIterator
 }
 
 pub use BigFixedError::{
-    IndexError as BigFixedIndexError
+    IndexError as BigFixedIndexError,
+    ParseError as BigFixedParseError,
+    FloatError as BigFixedFloatError,
+    IntError as BigFixedIntError,
+    BytesError as BigFixedBytesError,
+    ArrayError as BigFixedArrayError
 };
 
 impl From<IndexError> for BigFixedError {
@@ -23,10 +64,45 @@ impl From<IndexError> for BigFixedError {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl From<ParseError> for BigFixedError {
+    fn from(x: ParseError) -> BigFixedError {
+        BigFixedParseError(x)
+    }
+}
+
+impl From<FloatError> for BigFixedError {
+    fn from(x: FloatError) -> BigFixedError {
+        BigFixedFloatError(x)
+    }
+}
+
+impl From<IntError> for BigFixedError {
+    fn from(x: IntError) -> BigFixedError {
+        BigFixedIntError(x)
+    }
+}
+
+impl From<BytesError> for BigFixedError {
+    fn from(x: BytesError) -> BigFixedError {
+        BigFixedBytesError(x)
+    }
+}
+
+impl From<ArrayError> for BigFixedError {
+    fn from(x: ArrayError) -> BigFixedError {
+        BigFixedArrayError(x)
+    }
+}
+
+// Bodies of up to INLINE_DIGITS digits, enough for any native integer, are stored inline and only longer ones spill to the heap
+pub const INLINE_DIGITS: usize = 128 / DIGITBITS;
+
+pub type Body = SmallVec<[Digit; INLINE_DIGITS]>;
+
+#[derive(Clone, Debug)]
 pub struct BigFixed {
     pub head: Digit,
-    pub body: Vec<Digit>,
+    pub body: Body,
     pub position: Index
 }
 
@@ -42,7 +118,7 @@ impl BigFixed {
             high -= 1;
         }
         self.body.truncate(high);
-        if self.body.len() > 0 {
+        if !self.body.is_empty() {
             let mut low = 0;
             let len = self.body.len();
             while low < len && self.body[low] == 0 {
@@ -52,7 +128,7 @@ impl BigFixed {
             self.position += low;
         }
         // special case: zero
-        if self.head == 0 && self.body.len() == 0 {
+        if self.head == 0 && self.body.is_empty() {
             self.position = Index::Position(0);
         }
         Ok(())
@@ -102,10 +178,21 @@ impl BigFixed {
         }
     }
 
-    pub fn construct(head: Digit, body: Vec<Digit>, position: Index) -> Result<BigFixed, BigFixedError> {
+    // self if it is properly positioned, otherwise a copy which is. Anything built by this crate is properly positioned so copying is rare.
+    pub(crate) fn positioned(&self) -> Result<Cow<'_, BigFixed>, BigFixedError> {
+        if self.properly_positioned() {
+            Ok(Cow::Borrowed(self))
+        } else {
+            let mut copy = self.clone();
+            copy.fix_position()?;
+            Ok(Cow::Owned(copy))
+        }
+    }
+
+    pub fn construct<B: Into<Body>>(head: Digit, body: B, position: Index) -> Result<BigFixed, BigFixedError> {
         let mut returner = BigFixed {
             head,
-            body,
+            body: body.into(),
             position
         };
         returner.format()?;
@@ -133,7 +220,7 @@ impl BigFixed {
         if reserve > 0 {
             self.body.reserve(reserve);
             if add_low > 0 {
-                self.body.splice(0..0, repeat(0).take(add_low));
+                self.body.insert_many(0, repeat_n(0, add_low));
             }
             if add_high > 0 {
                 self.body.resize(self.body.len() + add_high, self.head);
@@ -150,7 +237,7 @@ impl BigFixed {
         self.ensure_valid_range(p, (p + 1isize)?)
     }
 
-    pub fn range_mut_iter(&mut self, low: Index, high: Index) -> Result<IterMut<Digit>, BigFixedError> {
+    pub fn range_mut_iter(&mut self, low: Index, high: Index) -> Result<IterMut<'_, Digit>, BigFixedError> {
         self.ensure_valid_range(low, high)?;
         Ok(self.body.iter_mut())
     }
@@ -163,14 +250,14 @@ impl BigFixed {
         let keep_high = min(high, body_high);
         let high = high.cast_to_position();
         Ok(
-            repeat(0).take((self.position - low)?.unsigned_value())
+            repeat_n(0, (self.position - low)?.unsigned_value())
             .chain(
-                self.body.iter().map(|x| *x)
+                self.body.iter().copied()
                 .skip((keep_low - self.position)?.unsigned_value())
                 .take((keep_high - keep_low)?.unsigned_value())
             )
             .chain(
-                repeat(self.head).take((high - body_high)?.unsigned_value())
+                repeat_n(self.head, (high - body_high)?.unsigned_value())
             )
         )
     }
@@ -209,7 +296,8 @@ impl BigFixed {
 
     pub fn overwrite(&mut self, src: &BigFixed) {
         self.head = src.head;
-        self.body.splice(0..self.body.len(), src.body.iter().map(|x| *x));
+        self.body.clear();
+        self.body.extend_from_slice(&src.body);
         self.position = src.position;
     }
 
@@ -228,6 +316,14 @@ impl BigFixed {
         self.head == 0 && self.body.iter().all(|&x| x == 0)
     }
 
+    // compares representations rather than values: head, body and position, including whether the position is a Position or a Bit
+    pub fn structurally_eq(&self, other: &BigFixed) -> bool {
+        self.head == other.head && self.body == other.body && match (self.position, other.position) {
+            (Index::Position(a), Index::Position(b)) | (Index::Bit(a), Index::Bit(b)) => a == b,
+            _ => false
+        }
+    }
+
     pub fn full_eq(&self, other: &BigFixed) -> Result<bool, BigFixedError> {
         for i in min(
             self.position.cast_to_position(),
@@ -246,14 +342,22 @@ impl BigFixed {
     }
 
     pub fn cutoff_index(&self, cutoff: Cutoff) -> Result<Index, BigFixedError> {
-        match (cutoff.fixed, cutoff.floating) {
-            (None, None) => Ok(self.position), // no cutoff
-            (Some(fixed), None) => Ok(max(self.position, fixed)),
-            (None, Some(floating)) => Ok(max(self.position, (self.greatest_bit_position()? - max(floating, Index::Bit(0)))?)),
-            (Some(fixed), Some(floating)) => Ok(min(
-                max(self.position, fixed),
-                max(self.position, (self.greatest_bit_position()? - max(floating, Index::Bit(0)))?))
-            )
+        BigFixed::cutoff_index_for(self.position, || self.greatest_bit_position(), cutoff)
+    }
+
+    // cutoff_index of a value with the given position and greatest bit position, the latter only computed for floating cutoffs
+    pub(crate) fn cutoff_index_for<F: FnOnce() -> Result<Index, BigFixedError>>(
+        position: Index, greatest_bit_position: F, cutoff: Cutoff
+    ) -> Result<Index, BigFixedError> {
+        let floating = match cutoff.floating {
+            Some(floating) => Some(max(position, (greatest_bit_position()? - max(floating, Index::Bit(0)))?)),
+            None => None
+        };
+        match (cutoff.fixed, floating) {
+            (None, None) => Ok(position), // no cutoff
+            (Some(fixed), None) => Ok(max(position, fixed)),
+            (None, Some(floating)) => Ok(floating),
+            (Some(fixed), Some(floating)) => Ok(min(max(position, fixed), floating))
         }
     }
 
@@ -298,13 +402,13 @@ impl BigFixed {
                     return self.cutoff(Cutoff {
                         fixed: cutoff.fixed,
                         floating: cutoff.floating,
-                        round: Rounding::Floor
+                        round: Rounding::Ceiling
                     });
                 } else {
                     return self.cutoff(Cutoff {
                         fixed: cutoff.fixed,
                         floating: cutoff.floating,
-                        round: Rounding::Ceiling
+                        round: Rounding::Floor
                     })
                 }
             },
@@ -313,13 +417,13 @@ impl BigFixed {
                     return self.cutoff(Cutoff {
                         fixed: cutoff.fixed,
                         floating: cutoff.floating,
-                        round: Rounding::Ceiling
+                        round: Rounding::Floor
                     });
                 } else {
                     return self.cutoff(Cutoff {
                         fixed: cutoff.fixed,
                         floating: cutoff.floating,
-                        round: Rounding::Floor
+                        round: Rounding::Ceiling
                     })
                 }
             }
@@ -330,7 +434,7 @@ impl BigFixed {
         }
         let diff = (as_bit - as_pos)?.value();
         if diff > 0 {
-            if self.body.len() == 0 {
+            if self.body.is_empty() {
                 self.body.push(self.head);
             }
             self[as_pos] &= ALLONES << diff;
@@ -344,7 +448,7 @@ impl BigFixed {
 
     pub const ZERO: BigFixed = BigFixed {
         head: 0,
-        body: vec![],
+        body: Body::new_const(),
         position: Index::Position(0)
     };
 }
@@ -355,48 +459,59 @@ impl CutsOff for BigFixed {
     }
 }
 
-impl fmt::Display for BigFixed {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.is_neg() {
-            return write!(f, "-{}", (-self).unwrap());
+impl BigFixed {
+    // Write the magnitude in radix 2^log2_radix, which is exact because every digit is a whole number of bits. The fractional part drops trailing
+    // zeros and is left off entirely for integers.
+    fn fmt_power_of_two(&self, f: &mut fmt::Formatter, log2_radix: usize, upper: bool, prefix: &str) -> fmt::Result {
+        // the fields are public so self may carry zero digits below its least significant one, which format drops before the trim
+        let mut abs = self.abs().map_err(|_| fmt::Error)?;
+        abs.format().map_err(|_| fmt::Error)?;
+        let k = log2_radix as isize;
+        let (high, low) = if abs.is_zero() {
+            (0, 0)
+        } else {
+            let greatest = abs.greatest_bit_position().map_err(|_| fmt::Error)?.bit_value().map_err(|_| fmt::Error)?;
+            let least = abs.position.bit_value().map_err(|_| fmt::Error)? + abs.body[0].trailing_zeros() as isize;
+            (max(greatest.div_euclid(k), 0), min(least.div_euclid(k), 0))
         };
-        let (mut digits, mut point) = self.to_digits_10().unwrap();
-        point = Index::castsize(digits.len()).unwrap() - point;
-        digits.reverse();
-        if point == 0 {
-            write!(f, "0").ok();
-        } else if point < 0 {
-            write!(f, "0.{}", "0".repeat(Index::uncastsize(-point).unwrap())).ok();
-        }
-        for d in digits {
-            if point == 0isize {
-                write!(f, ".").ok();
+        let mut digits = String::new();
+        for i in (low..=high).rev() {
+            if i == -1 {
+                digits.push('.');
             }
-            point -= 1isize;
-            write!(f, "{}", d).ok();
+            let mut d = 0;
+            for b in (0..k).rev() {
+                d = (d << 1) | abs[Index::Bit(i*k + b)] as u32;
+            }
+            let c = core::char::from_digit(d, 1 << log2_radix).unwrap();
+            digits.push(if upper {c.to_ascii_uppercase()} else {c});
         }
-        write!(f, "{}", "0".repeat(Index::saturating_unsigned(point))).ok();
-        write!(f, "")
+        f.pad_integral(!self.is_neg(), prefix, &digits)
     }
 }
 
+// The power of two radixes are exact. With the alternate flag they are prefixed 0b, 0o, 0x to match the literals accepted by parse_c.
+
 impl fmt::Binary for BigFixed {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.is_neg() {
-            return write!(f, "-{:b}", (-self).unwrap());
-        };
-        let start = self.greatest_bit_position().unwrap().bit_value().unwrap();
-        if start < 0 {
-            write!(f, "{}", "0".repeat(Index::uncastsize(-start).unwrap())).ok();
-            write!(f, ".").ok();
-        };
-        for p in (self.position.bit_value().unwrap()..=start).rev() {
-            //println!("p {}", p);
-            write!(f, "{}", self[Index::Bit(p)]).ok();
-            if p == 0 {
-                write!(f, ".").ok();
-            }
-        }
-        Ok(())
+        self.fmt_power_of_two(f, 1, false, "0b")
+    }
+}
+
+impl fmt::Octal for BigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_power_of_two(f, 3, false, "0o")
+    }
+}
+
+impl fmt::LowerHex for BigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_power_of_two(f, 4, false, "0x")
+    }
+}
+
+impl fmt::UpperHex for BigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_power_of_two(f, 4, true, "0x")
     }
 }
@@ -0,0 +1,413 @@
+/*
+    ArrayBigFixed<N> is a BigFixed whose body lives inline in a [Digit; N], for hot paths and environments without an allocator. It has the same head,
+    body and position semantics (body[..len] little endian from position, the head repeating above) and the same Index based indexing. Nothing ever
+    reallocates: any result whose formatted body needs more than N digits fails with CapacityExceeded instead.
+
+    Results are produced as a stream of digits from the lowest position up. The Collector drops low zeros as they arrive and remembers digits past
+    capacity, which are fine exactly when they all equal the final head and so would be trimmed by formatting anyway. Addition and subtraction
+    stream two's complement sums; multiplication streams the product of the magnitudes column by column and negates it on the way out.
+*/
+
+use crate::{digit::*, Index as Indx, BigFixed, Body, BigFixedError, BigFixedIndexError, BigFixedArrayError, IndexError};
+
+use core::{ops::{Index, IndexMut}, cmp::{max, min}, convert::TryFrom};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArrayError {
+    // the formatted body needs more than N digits
+    CapacityExceeded
+}
+
+pub use ArrayError::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ArrayBigFixed<const N: usize> {
+    pub head: Digit,
+    pub body: [Digit; N],
+    // the number of digits of body in use
+    pub len: usize,
+    // always Index::Position
+    pub position: Indx
+}
+
+// builds a formatted ArrayBigFixed from digits pushed from the lowest position up
+struct Collector<const N: usize> {
+    body: [Digit; N],
+    len: usize,
+    // the position of the next digit until the first nonzero one, then of body[0]
+    position: isize,
+    started: bool,
+    // the value of the digits which did not fit, if they all agree
+    overflow: Option<Digit>,
+    mixed: bool
+}
+
+impl<const N: usize> Collector<N> {
+    fn new(position: isize) -> Collector<N> {
+        Collector {
+            body: [0; N],
+            len: 0,
+            position,
+            started: false,
+            overflow: None,
+            mixed: false
+        }
+    }
+
+    fn push(&mut self, d: Digit) -> Result<(), BigFixedError> {
+        if !self.started {
+            if d == 0 {
+                self.position = self.position.checked_add(1).ok_or(BigFixedIndexError(IndexError::AdditionOverflow))?;
+                return Ok(());
+            }
+            self.started = true;
+        }
+        if self.len < N {
+            self.body[self.len] = d;
+            self.len += 1;
+        } else {
+            match self.overflow {
+                None => self.overflow = Some(d),
+                Some(v) if v != d => self.mixed = true,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // push d count times, which takes at most N + 1 real pushes since anything past capacity only needs to agree
+    fn push_repeat(&mut self, d: Digit, count: usize) -> Result<(), BigFixedError> {
+        if !self.started && d == 0 {
+            self.position = self.position.checked_add_unsigned(count).ok_or(BigFixedIndexError(IndexError::AdditionOverflow))?;
+            return Ok(());
+        }
+        for _ in 0..min(count, N + 1) {
+            self.push(d)?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, head: Digit) -> Result<ArrayBigFixed<N>, BigFixedError> {
+        let head = if head == 0 {0} else {ALLONES};
+        if self.mixed || self.overflow.is_some_and(|v| v != head) {
+            return Err(BigFixedArrayError(CapacityExceeded));
+        }
+        while self.len > 0 && self.body[self.len - 1] == head {
+            self.len -= 1;
+        }
+        if head == 0 && self.len == 0 {
+            self.position = 0;
+        }
+        Ok(ArrayBigFixed {
+            head,
+            body: self.body,
+            len: self.len,
+            position: Indx::Position(self.position)
+        })
+    }
+}
+
+impl<const N: usize> ArrayBigFixed<N> {
+    pub const ZERO: ArrayBigFixed<N> = ArrayBigFixed {
+        head: 0,
+        body: [0; N],
+        len: 0,
+        position: Indx::Position(0)
+    };
+
+    // the formatted value of head and body from position, which may be a Bit index
+    pub fn construct(head: Digit, body: &[Digit], position: Indx) -> Result<ArrayBigFixed<N>, BigFixedError> {
+        let head = if head == 0 {0} else {ALLONES};
+        let shift = position.bit_position_excess() as usize;
+        let mut collector = Collector::new(position.cast_to_position().value());
+        if shift == 0 {
+            for &d in body.iter() {
+                collector.push(d)?;
+            }
+        } else {
+            let mut below = 0;
+            for &d in body.iter().chain([head].iter()) {
+                collector.push((d << shift) | (below >> (DIGITBITS - shift)))?;
+                below = d;
+            }
+        }
+        collector.finish(head)
+    }
+
+    pub fn body(&self) -> &[Digit] {
+        &self.body[..self.len]
+    }
+
+    pub fn format(&mut self) -> Result<(), BigFixedError> {
+        *self = ArrayBigFixed::construct(self.head, &self.body[..self.len], self.position)?;
+        Ok(())
+    }
+
+    pub fn is_neg(&self) -> bool {
+        self.head != 0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.head == 0 && self.body().iter().all(|&x| x == 0)
+    }
+
+    // the least position which is outside of the range contained in body
+    pub fn body_high(&self) -> Result<Indx, BigFixedError> {
+        Ok((self.position + self.len)?)
+    }
+
+    // the digit at a position
+    fn digit(&self, position: isize) -> Digit {
+        let low = self.position.value();
+        if position < low {
+            0
+        } else if position.abs_diff(low) >= self.len {
+            self.head
+        } else {
+            self.body[position.abs_diff(low)]
+        }
+    }
+
+    fn in_body(&self, position: isize) -> bool {
+        let low = self.position.value();
+        position >= low && position.abs_diff(low) < self.len
+    }
+
+    // The end (exclusive) of the run of positions from p on which neither self nor other crosses into or out of its body, at most high + 1. Outside
+    // both bodies every digit in such a run is the same.
+    fn run_end(&self, other: &ArrayBigFixed<N>, p: isize, high: isize) -> Result<isize, BigFixedError> {
+        let bounds = [self.position, self.body_high()?, other.position, other.body_high()?];
+        Ok(bounds.iter().map(|b| b.value()).filter(|&b| b > p).min().unwrap_or(high + 1))
+    }
+
+    // Restructure if necessary so that position is within body. Breaks format so reformat afterwards. Returns whether restructuring was necessary.
+    pub fn ensure_valid_position(&mut self, position: Indx) -> Result<bool, BigFixedError> {
+        let p = position.cast_to_position().value();
+        let low = self.position.value();
+        if p < low {
+            let add = Indx::uncastsize(low - p)?;
+            if self.len + add > N {
+                return Err(BigFixedArrayError(CapacityExceeded));
+            }
+            self.body.copy_within(0..self.len, add);
+            self.body[..add].fill(0);
+            self.len += add;
+            self.position = Indx::Position(p);
+            Ok(true)
+        } else if p - low >= self.len as isize {
+            let new_len = Indx::uncastsize(p - low)? + 1;
+            if new_len > N {
+                return Err(BigFixedArrayError(CapacityExceeded));
+            }
+            self.body[self.len..new_len].fill(self.head);
+            self.len = new_len;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    // self + other (or self - other), streaming a + b + carry (or a + !b + 1) over every position where either has body digits. Between the bodies
+    // both digits are constant and the carry settles after two positions, so the rest of such a run repeats the last digit.
+    fn combine(&self, other: &ArrayBigFixed<N>, subtract: bool) -> Result<ArrayBigFixed<N>, BigFixedError> {
+        let low = min(self.position, other.position).value();
+        let high = max(self.body_high()?, other.body_high()?).value();
+        let sum = |p: isize, carry: DoubleDigit| {
+            let b = if subtract {!other.digit(p)} else {other.digit(p)};
+            (self.digit(p) as DoubleDigit) + (b as DoubleDigit) + carry
+        };
+        let mut collector = Collector::new(low);
+        let mut carry: DoubleDigit = if subtract {1} else {0};
+        let mut p = low;
+        while p <= high {
+            let end = self.run_end(other, p, high)?;
+            let steps = if self.in_body(p) || other.in_body(p) {end - p} else {min(end - p, 2)};
+            let mut last = 0;
+            for q in p..(p + steps) {
+                let s = sum(q, carry);
+                last = s as Digit;
+                collector.push(last)?;
+                carry = s >> DIGITBITS;
+            }
+            collector.push_repeat(last, end.abs_diff(p) - steps.unsigned_abs())?;
+            p = end;
+        }
+        // one position past both bodies the digit is the head
+        collector.finish(sum(p, carry) as Digit)
+    }
+
+    pub fn add_assign(&mut self, other: &ArrayBigFixed<N>) -> Result<(), BigFixedError> {
+        *self = self.combine(other, false)?;
+        Ok(())
+    }
+
+    pub fn sub_assign(&mut self, other: &ArrayBigFixed<N>) -> Result<(), BigFixedError> {
+        *self = self.combine(other, true)?;
+        Ok(())
+    }
+
+    pub fn negate(&mut self) -> Result<(), BigFixedError> {
+        *self = ArrayBigFixed::ZERO.combine(self, true)?;
+        Ok(())
+    }
+
+    // digits of |self| for formatted self, which fit in magnitude_len digits
+    fn magnitude_len(&self) -> usize {
+        if self.is_neg() && self.len == 0 {1} else {self.len}
+    }
+
+    fn magnitude_digit(&self, i: usize) -> Digit {
+        if !self.is_neg() {
+            self.body[i]
+        } else if self.len == 0 {
+            1
+        } else if i == 0 {
+            // the lowest digit of a formatted body is nonzero so the + 1 stops here
+            (!self.body[0]).wrapping_add(1)
+        } else {
+            !self.body[i]
+        }
+    }
+
+    // the product of the magnitudes column by column, negated as it streams out if the signs differ
+    pub fn mul_assign(&mut self, other: &ArrayBigFixed<N>) -> Result<(), BigFixedError> {
+        let mut a = *self;
+        a.format()?;
+        let mut b = *other;
+        b.format()?;
+        let position = a.position.value().checked_add(b.position.value()).ok_or(BigFixedIndexError(IndexError::AdditionOverflow))?;
+        let (a_len, b_len) = (a.magnitude_len(), b.magnitude_len());
+        if a_len == 0 || b_len == 0 {
+            *self = ArrayBigFixed::ZERO;
+            return Ok(());
+        }
+        let neg = a.is_neg() != b.is_neg();
+        let mut collector = Collector::new(position);
+        // the running column sum is high * 2^DOUBLEBITS + low
+        let (mut low, mut high): (DoubleDigit, DoubleDigit) = (0, 0);
+        let mut negate_carry = true;
+        for k in 0..(a_len + b_len) {
+            for i in k.saturating_sub(b_len - 1)..min(k + 1, a_len) {
+                let product = (a.magnitude_digit(i) as DoubleDigit) * (b.magnitude_digit(k - i) as DoubleDigit);
+                let (sum, overflow) = low.overflowing_add(product);
+                low = sum;
+                high += overflow as DoubleDigit;
+            }
+            let mut d = low as Digit;
+            if neg {
+                // two's complement on the fly: !d + 1 while the lower digits are all zero
+                let (negated, carry) = (!d).overflowing_add(negate_carry as Digit);
+                d = negated;
+                negate_carry = carry;
+            }
+            collector.push(d)?;
+            low = (low >> DIGITBITS) | (high << DIGITBITS);
+            high >>= DIGITBITS;
+        }
+        *self = collector.finish(if neg {ALLONES} else {0})?;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Default for ArrayBigFixed<N> {
+    fn default() -> ArrayBigFixed<N> {
+        ArrayBigFixed::ZERO
+    }
+}
+
+// compares values digit by digit so unused capacity and formatting do not matter, checking one digit of each run between the bodies
+impl<const N: usize> PartialEq for ArrayBigFixed<N> {
+    fn eq(&self, other: &ArrayBigFixed<N>) -> bool {
+        if self.head != other.head {
+            return false;
+        }
+        let low = min(self.position, other.position).value();
+        let high = max(self.body_high().unwrap(), other.body_high().unwrap()).value();
+        let mut p = low;
+        while p < high {
+            let end = self.run_end(other, p, high).unwrap();
+            let check = if self.in_body(p) || other.in_body(p) {end} else {p + 1};
+            if !(p..check).all(|q| self.digit(q) == other.digit(q)) {
+                return false;
+            }
+            p = end;
+        }
+        true
+    }
+}
+
+impl<const N: usize> Eq for ArrayBigFixed<N> {}
+
+impl<const N: usize> Index<Indx> for ArrayBigFixed<N> {
+    type Output = Digit;
+    fn index(&self, position: Indx) -> &Digit {
+        match position {
+            Indx::Position(p) => {
+                let shifted = p - self.position.value();
+                if shifted >= self.len as isize {
+                    &self.head
+                } else if shifted >= 0 {
+                    &self.body[shifted as usize]
+                } else {
+                    &0
+                }
+            },
+            Indx::Bit(b) => {
+                let d = self[Indx::bit_to_position(b)];
+                if (d >> position.bit_position_excess()) & 1 == 1 {
+                    &1
+                } else {
+                    &0
+                }
+            }
+        }
+    }
+}
+
+impl<const N: usize> Index<isize> for ArrayBigFixed<N> {
+    type Output = Digit;
+    fn index(&self, position: isize) -> &Digit {
+        &self[Indx::Position(position)]
+    }
+}
+
+// Gives a reference to the digit in the corresponding position regardless of Index type (Bit/Position). Panics if the position is out of capacity.
+impl<const N: usize> IndexMut<Indx> for ArrayBigFixed<N> {
+    fn index_mut(&mut self, position: Indx) -> &mut Digit {
+        let position = position.cast_to_position();
+        self.ensure_valid_position(position).unwrap();
+        &mut self.body[(position.value() - self.position.value()) as usize]
+    }
+}
+
+impl<const N: usize> IndexMut<isize> for ArrayBigFixed<N> {
+    fn index_mut(&mut self, position: isize) -> &mut Digit {
+        self.index_mut(Indx::Position(position))
+    }
+}
+
+impl<const N: usize> TryFrom<&BigFixed> for ArrayBigFixed<N> {
+    type Error = BigFixedError;
+    fn try_from(x: &BigFixed) -> Result<ArrayBigFixed<N>, BigFixedError> {
+        ArrayBigFixed::construct(x.head, &x.body, x.position)
+    }
+}
+
+impl<const N: usize> TryFrom<BigFixed> for ArrayBigFixed<N> {
+    type Error = BigFixedError;
+    fn try_from(x: BigFixed) -> Result<ArrayBigFixed<N>, BigFixedError> {
+        ArrayBigFixed::try_from(&x)
+    }
+}
+
+impl<const N: usize> From<&ArrayBigFixed<N>> for BigFixed {
+    fn from(x: &ArrayBigFixed<N>) -> BigFixed {
+        BigFixed::construct(x.head, Body::from_slice(x.body()), x.position).unwrap()
+    }
+}
+
+impl<const N: usize> From<ArrayBigFixed<N>> for BigFixed {
+    fn from(x: ArrayBigFixed<N>) -> BigFixed {
+        BigFixed::from(&x)
+    }
+}
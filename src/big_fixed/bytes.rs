@@ -0,0 +1,169 @@
+/*
+    Canonical byte encoding. A value is written as
+
+        head flag (0 for nonnegative, 1 for negative) | varint position | varint length | length body bytes
+
+    where the body is the two's complement expansion in little endian bytes, the position is the weight of its lowest byte in bytes (zigzag) and
+    both varints are LEB128. None of this depends on the Digit width: the body is trimmed byte by byte, dropping zero bytes from the bottom and
+    bytes equal to the head from the top, with zero as the empty nonnegative body at position 0. Every value therefore has exactly one encoding and
+    from_bytes rejects anything else, so encodings can be compared and hashed directly.
+*/
+
+use crate::{digit::*, Index, IndexError, BigFixed, Body, BigFixedError, BigFixedIndexError, BigFixedBytesError};
+
+use core::iter::repeat_n;
+
+use alloc::{vec::Vec, vec};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BytesError {
+    // the input ended inside a value
+    Truncated,
+    // from_bytes got more input after a complete value
+    TrailingBytes,
+    // the head flag is neither 0 nor 1
+    InvalidHead,
+    // a varint has redundant high zero groups or does not fit in isize/usize
+    InvalidVarint,
+    // the body has a zero lowest byte or a highest byte equal to the head, or zero has a nonzero position
+    NonCanonical,
+    // the body is longer than a bounded encoding allows
+    TooLarge,
+    // a bounded encoding has nonzero bits more than MAX_PARSE_BITS from the binary point
+    PositionOutOfRange
+}
+
+pub use BytesError::*;
+
+fn write_varint(out: &mut Vec<u8>, mut x: u64) {
+    while x >= 0x80 {
+        out.push((x as u8) | 0x80);
+        x >>= 7;
+    }
+    out.push(x as u8);
+}
+
+// next yields the input one byte at a time
+fn read_varint<F: FnMut() -> Result<u8, BigFixedError>>(next: &mut F) -> Result<u64, BigFixedError> {
+    let mut returner: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = next()?;
+        let group = (byte & 0x7f) as u64;
+        if shift >= 64 || (group << shift) >> shift != group {
+            return Err(BigFixedBytesError(InvalidVarint));
+        }
+        returner |= group << shift;
+        if byte & 0x80 == 0 {
+            // the last group may only be zero when it is the only one
+            if byte == 0 && shift > 0 {
+                return Err(BigFixedBytesError(InvalidVarint));
+            }
+            return Ok(returner);
+        }
+        shift += 7;
+    }
+}
+
+// The head, byte position and body length at the front of an encoding. Readers check the length against what they can afford before reading the
+// body with from_parts.
+pub(crate) fn read_header<F: FnMut() -> Result<u8, BigFixedError>>(mut next: F) -> Result<(Digit, isize, usize), BigFixedError> {
+    let head = match next()? {
+        0 => 0,
+        1 => ALLONES,
+        _ => return Err(BigFixedBytesError(InvalidHead))
+    };
+    let zigzag = read_varint(&mut next)?;
+    let position = isize::try_from((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64)).map_err(|_| InvalidVarint)?;
+    let len = usize::try_from(read_varint(&mut next)?).map_err(|_| InvalidVarint)?;
+    Ok((head, position, len))
+}
+
+// the value with the given head, byte position and body bytes, which must be canonical
+pub(crate) fn from_parts(head: Digit, position: isize, body: &[u8]) -> Result<BigFixed, BigFixedError> {
+    let head_byte = head as u8;
+    let canonical = match (body.first(), body.last()) {
+        (Some(&lowest), Some(&highest)) => lowest != 0 && highest != head_byte,
+        _ => head != 0 || position == 0
+    };
+    if !canonical {
+        return Err(BigFixedBytesError(NonCanonical));
+    }
+    // realign the bytes to Digit boundaries
+    let len = body.len();
+    let offset = Index::uncastsize(position.rem_euclid(DIGITBYTES as isize))?;
+    let total = (offset + len).div_ceil(DIGITBYTES) * DIGITBYTES;
+    let aligned: Vec<u8> = repeat_n(0, offset).chain(body.iter().copied()).chain(repeat_n(head_byte, total - offset - len)).collect();
+    let digits: Body = aligned.chunks(DIGITBYTES).map(digit_from_bytes).collect();
+    BigFixed::construct(head, digits, Index::Position(position.div_euclid(DIGITBYTES as isize)))
+}
+
+impl BigFixed {
+    // the head, byte position and trimmed body bytes of the canonical encoding
+    pub(crate) fn canonical_parts(&self) -> Result<(Digit, isize, Vec<u8>), BigFixedError> {
+        let mut formatted = self.clone();
+        formatted.format()?;
+        let head_byte = formatted.head as u8;
+        let mut bytes: Vec<u8> = formatted.body.iter().flat_map(|d| d.to_le_bytes()).collect();
+        let mut high = bytes.len();
+        while high > 0 && bytes[high - 1] == head_byte {
+            high -= 1;
+        }
+        let mut low = 0;
+        while low < high && bytes[low] == 0 {
+            low += 1;
+        }
+        let position = if low == high && !formatted.is_neg() {
+            0
+        } else {
+            let digit_position = formatted.position.cast_to_position().value();
+            digit_position.checked_mul(DIGITBYTES as isize).ok_or(BigFixedIndexError(IndexError::MultiplicationOverflow))?
+                .checked_add(Index::castsize(low)?).ok_or(BigFixedIndexError(IndexError::AdditionOverflow))?
+        };
+        bytes.truncate(high);
+        bytes.drain(0..low);
+        Ok((formatted.head, position, bytes))
+    }
+
+    // append the canonical encoding of self to out
+    pub fn write_bytes(&self, out: &mut Vec<u8>) -> Result<(), BigFixedError> {
+        let (head, position, body) = self.canonical_parts()?;
+        out.push(if head != 0 {1} else {0});
+        let position = position as i64;
+        write_varint(out, ((position << 1) ^ (position >> 63)) as u64);
+        write_varint(out, body.len() as u64);
+        out.extend_from_slice(&body);
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BigFixedError> {
+        let mut returner = vec![];
+        self.write_bytes(&mut returner)?;
+        Ok(returner)
+    }
+
+    // Decode one value from the front of input and advance input past it. Fails on anything which is not a canonical encoding.
+    pub fn read_bytes(input: &mut &[u8]) -> Result<BigFixed, BigFixedError> {
+        let (head, position, len) = read_header(|| {
+            let (&byte, rest) = input.split_first().ok_or(BigFixedBytesError(Truncated))?;
+            *input = rest;
+            Ok(byte)
+        })?;
+        if input.len() < len {
+            return Err(BigFixedBytesError(Truncated));
+        }
+        let (body, rest) = input.split_at(len);
+        *input = rest;
+        from_parts(head, position, body)
+    }
+
+    // the value encoded by exactly the given bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<BigFixed, BigFixedError> {
+        let mut input = bytes;
+        let returner = BigFixed::read_bytes(&mut input)?;
+        if !input.is_empty() {
+            return Err(BigFixedBytesError(TrailingBytes));
+        }
+        Ok(returner)
+    }
+}
@@ -1,6 +1,29 @@
-use crate::{digit::*, Index, Cutoff, cutoff::*, BigFixed, BigFixedError};
+use crate::{digit::*, Index, Cutoff, cutoff::*, BigFixed, Body, BigFixedError, BigFixedFloatError, BigFixedIntError, big_fixed::magnitude::*};
 
-use std::{convert::{From}, cmp::{max}};
+use core::{convert::{From, TryFrom}, cmp::{max}};
+
+use alloc::{vec::Vec, vec};
+
+use paste::paste;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FloatError {
+    NaN,
+    Infinity,
+    NegativeInfinity
+}
+
+pub use FloatError::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IntError {
+    // outside the range of the integer type
+    Overflow,
+    // nonzero fractional part
+    Fractional
+}
+
+pub use IntError::*;
 
 impl BigFixed {
     // little endian bytes
@@ -9,7 +32,7 @@ impl BigFixed {
         let good_len = bytes.len() / DIGITBYTES;
         let good_bytes_len = good_len * DIGITBYTES;
         // one extra for byte-extended digit
-        let mut data: Vec<Digit> = Vec::with_capacity(good_len + 1);
+        let mut data = Body::with_capacity(good_len + if bytes.len() != good_bytes_len {1} else {0});
         data.extend(
             (0..good_len).map(
                 |i| i * DIGITBYTES
@@ -27,7 +50,7 @@ impl BigFixed {
             int_bytes[0..fill_len].clone_from_slice(&bytes[good_bytes_len..bytes.len()]);
             data.push(digit_from_bytes(&int_bytes));
         }
-        let is_neg = !unsigned && data.len() > 0 && data[data.len()-1] >= GREATESTBIT;
+        let is_neg = !unsigned && !data.is_empty() && data[data.len()-1] >= GREATESTBIT;
         BigFixed::construct(
             if is_neg {ALLONES} else {0},
             data,
@@ -35,80 +58,100 @@ impl BigFixed {
         )
     }
 
-    // TODO: float special cases -- zeroes, infinities, NANs
-
-    // load float into a BigFixed as an unsigned integer with identical bits then call this to interpret it correctly
+    // Load float into a BigFixed as an unsigned integer with identical bits then call this to interpret it. Subnormals are exact; infinities and
+    // NaNs have no BigFixed value and give a FloatError. Negative zero is zero.
     // float format: [0][sign bit][exponent + bias][significand].[0]
-    pub fn float_from_bits(mut self, exponent_len: usize, exponent_bias: isize, significand_len: usize) -> Result<BigFixed, BigFixedError> {
+    pub fn float_from_bits(self, exponent_len: usize, exponent_bias: isize, significand_len: usize) -> Result<BigFixed, BigFixedError> {
         assert!(!self.is_neg() && self.position >= 0isize, "improper float format");
-        // get sign
-        self = self.shift(Index::Bit(-Index::castsize(exponent_len + significand_len)?))?;
-        let is_neg = self[0] == 1;
-        self[0] = 0;
-        // get exponent
-        self = self.shift(Index::Bit(Index::castsize(exponent_len)?))?;
-        let exp = Index::castsize(<usize>::from(&self))? - exponent_bias;
-        for i in 0..max(0, self.body_high()?.value()) {
-            self[i] = 0;
-        }
-        // introduct implicit significand greatest bit (except for the special case 0)
-        if self.is_zero() && exp == exponent_bias {
-            self.format()?;
-            return Ok(self);
+        let (mut bits, low) = self.magnitude()?;
+        shl_bits(&mut bits, Index::saturating_unsigned(low));
+        let mut mag = bit_field(&bits, 0, significand_len);
+        let exponent = Index::castsize(mag_to_u128(&bit_field(&bits, significand_len, exponent_len)) as usize)?;
+        let neg = !bit_field(&bits, significand_len + exponent_len, 1).is_empty();
+        let max_exponent = Index::castsize((1usize << exponent_len) - 1)?;
+        let significand_len = Index::castsize(significand_len)?;
+        if exponent == max_exponent {
+            return Err(BigFixedFloatError(if !mag.is_empty() {NaN} else if neg {NegativeInfinity} else {Infinity}));
         }
-        self[0] = 1;
-        self = self.shift(Index::Bit(exp))?;
-        if is_neg {
-            self.negate()?;
+        let bit = if exponent == 0 {
+            // subnormal, no implicit bit and the least exponent
+            1 - exponent_bias - significand_len
+        } else {
+            add_mag(&mut mag, &implicit_bit(significand_len)?, 0);
+            exponent - exponent_bias - significand_len
+        };
+        let mut returner = BigFixed::construct(0, mag, Index::Bit(bit))?;
+        if neg {
+            returner.negate()?;
         }
-        Ok(self)
+        Ok(returner)
     }
 
-    // recasts self as an unsigned integer whose bits match the specified pattern, saturating the exponent and truncating the significand
-    pub fn float_to_bits(mut self, exponent_len: usize, exponent_bias: isize, significand_len: usize) -> Result<BigFixed, BigFixedError> {
-        if self.is_zero() {
-            return Ok(self);
-        }
+    // Recasts self as an unsigned integer holding the bits of the float nearest to self under round. Values below the least normal exponent
+    // underflow gradually through the subnormals to a signed zero. Values too large overflow to infinity, or to the greatest finite value when
+    // rounding towards zero.
+    pub fn float_to_bits(self, exponent_len: usize, exponent_bias: isize, significand_len: usize, round: Rounding) -> Result<BigFixed, BigFixedError> {
         let neg = self.is_neg();
-        if neg {
-            self.negate()?;
-        };
-        // head is 0
-        let position = self.greatest_bit_position()?;
-        self = self.shift((-position)?)?;
-        // self.int() == 1, pop it because it is implied in float format
-        self.body.pop();
-        let low_pos = (-Index::Bit(Index::castsize(significand_len + DIGITBITS - 1)?))?;
-        self.cutoff(Cutoff{
-            fixed: Some(low_pos),
-            floating: None,
-            round: Rounding::Floor
-        })?;
-        if self.body.len() > 0 {
-            self[low_pos] &= ALLONES << (DIGITBITS - (significand_len % DIGITBITS));
-        }
-        let mut exp = (position + exponent_bias)?.value();
-        let max_exp = Index::castsize((ALLONES >> (DIGITBITS - exponent_len)) as usize)?;
-        // saturation cases
-        if exp < 0 {
-            // exponent too low, setting to zero
-            exp = 0;
-            self.clear();
-        } else if exp > max_exp {
-            // exponent too high, setting to infty
-            exp = max_exp;
-            self.clear();
+        let sig = Index::castsize(significand_len)?;
+        let max_field = Index::castsize((1usize << exponent_len) - 1)?;
+        let min_exp = 1 - exponent_bias;
+        let max_exp = max_field - 1 - exponent_bias;
+        let (mag, bit) = self.magnitude()?;
+        let mut rounded = self;
+        if !mag.is_empty() {
+            // round to the unit in the last place of the binade of self, which is fixed below the least normal exponent
+            let exp = Index::castsize(bit_len(&mag))? - 1 + bit;
+            rounded.cutoff(Cutoff {
+                fixed: Some(Index::Bit(max(exp, min_exp) - sig)),
+                floating: None,
+                round
+            })?;
         }
-        self |= &BigFixed::from(exp);
-        self = self.shift((-Index::Bit(Index::castsize(exponent_len)?))?)?;
-        if neg {
-            self |= &BigFixed::from(1);
-        }
-        self = self.shift((Index::Bit(Index::castsize(exponent_len)?) + Index::Bit(Index::castsize(significand_len)?))?)?;
-        Ok(self)
+        let (mag, bit) = rounded.magnitude()?;
+        let (field, fraction) = if mag.is_empty() {
+            (0, vec![])
+        } else {
+            let exp = Index::castsize(bit_len(&mag))? - 1 + bit;
+            if exp > max_exp {
+                let to_infinity = match round {
+                    Rounding::Floor => neg,
+                    Rounding::Ceiling => !neg,
+                    Rounding::TowardsZero => false,
+                    Rounding::Round | Rounding::AwayFromZero => true
+                };
+                if to_infinity {
+                    (max_field, vec![])
+                } else {
+                    let mut greatest = implicit_bit(sig)?;
+                    sub_mag(&mut greatest, &[1], 0);
+                    (max_field - 1, greatest)
+                }
+            } else {
+                let (field, scale) = if exp < min_exp {(0, sig - min_exp)} else {(exp + exponent_bias, sig - exp)};
+                let mut fraction = mag;
+                shl_bits(&mut fraction, Index::uncastsize(bit + scale)?);
+                if field > 0 {
+                    sub_mag(&mut fraction, &implicit_bit(sig)?, 0);
+                }
+                (field, fraction)
+            }
+        };
+        let mut bits = if neg {vec![1]} else {vec![]};
+        shl_bits(&mut bits, exponent_len);
+        add_mag(&mut bits, &mag_from_u128(field as u128), 0);
+        shl_bits(&mut bits, significand_len);
+        add_mag(&mut bits, &fraction, 0);
+        BigFixed::construct(0, bits, Index::Position(0))
     }
 }
 
+// 2^significand_len, the leading bit of a normal float
+fn implicit_bit(significand_len: isize) -> Result<Vec<Digit>, BigFixedError> {
+    let mut returner = vec![1];
+    shl_bits(&mut returner, Index::uncastsize(significand_len)?);
+    Ok(returner)
+}
+
 impl From<&[u8]> for BigFixed {
     fn from(bytes: &[u8]) -> BigFixed {
         BigFixed::int_from_bytes(bytes, true).unwrap()
@@ -157,82 +200,82 @@ from_unsigned_int!(u64, 8);
 from_signed_int!(i128, 16);
 from_unsigned_int!(u128, 16);
 
-// to_unsigned_int is a bit casting over the bits of BigFixed::from(ALLONES)
+// Conversions to native integers. TryFrom fails unless the value is an integer in range. The explicit methods choose what happens otherwise:
+// to_*_wrapping keeps the low bits of floor(self) in two's complement, to_*_saturating clamps floor(self) to the range and to_*_rounded rounds to
+// an integer under a Rounding and then fails on overflow.
 
-macro_rules! to_unsigned_int {
-    ($int: ty, $num_bytes: expr) => {
-        impl From<&BigFixed> for $int {
-            fn from(x: &BigFixed) -> $int {
-                if DIGITBYTES >= $num_bytes {
-                    let mut bytes = [0u8; $num_bytes];
-                    let d = x[0].to_le_bytes();
-                    bytes[0..$num_bytes].copy_from_slice(&d[0..$num_bytes]);
-                    <$int>::from_le_bytes(bytes)
-                } else {
-                    assert_eq!($num_bytes % DIGITBYTES, 0, "byte number mismatch");
-                    let len = $num_bytes / DIGITBYTES;
+macro_rules! to_int {
+    ($int: ident, $num_bytes: expr) => {
+        paste! {
+            impl BigFixed {
+                pub fn [<to_ $int _wrapping>](&self) -> $int {
                     let mut bytes = [0u8; $num_bytes];
-                    let mut on = 0;
-                    for i in 0..len {
-                        let d = x[i as isize].to_le_bytes();
-                        for j in 0..DIGITBYTES {
-                            bytes[on] = d[j];
-                            on += 1;
-                        }
+                    for (i, b) in bytes.iter_mut().enumerate() {
+                        *b = self[(i / DIGITBYTES) as isize].to_le_bytes()[i % DIGITBYTES];
                     }
                     <$int>::from_le_bytes(bytes)
                 }
-            }
-        }
-    };
-}
 
-to_unsigned_int!(usize, SIZEBYTES);
-to_unsigned_int!(u8, 1);
-to_unsigned_int!(u16, 2);
-to_unsigned_int!(u32, 4);
-to_unsigned_int!(u64, 8);
-to_unsigned_int!(u128, 16);
-
-// to_signed_int is a saturating cast
-
-macro_rules! to_signed_int {
-    ($int: ty, $unsigned: ty, $num_bytes: expr) => {
-        impl From<&BigFixed> for $int {
-            fn from(x: &BigFixed) -> $int {
-                let cutoff: $unsigned = 1 as $unsigned << (8 * $num_bytes - 1);
-                let mut c = BigFixed::from(cutoff);
-                if x >= &c {
-                    // saturating: too high
-                    (cutoff - 1) as $int
-                } else {
-                    c.negate().unwrap();
-                    if x < &c {
-                        // saturating: too low
-                        cutoff as $int
+                pub fn [<to_ $int _saturating>](&self) -> $int {
+                    if self >= &BigFixed::from(<$int>::MAX) {
+                        <$int>::MAX
+                    } else if self < &BigFixed::from(<$int>::MIN) {
+                        <$int>::MIN
                     } else {
-                        <$unsigned>::from(x) as $int
+                        self.[<to_ $int _wrapping>]()
+                    }
+                }
+
+                pub fn [<to_ $int _rounded>](&self, round: Rounding) -> Result<$int, BigFixedError> {
+                    let mut rounded = self.clone();
+                    rounded.cutoff(Cutoff {
+                        fixed: Some(Index::Position(0)),
+                        floating: None,
+                        round
+                    })?;
+                    <$int>::try_from(&rounded)
+                }
+            }
+
+            impl TryFrom<&BigFixed> for $int {
+                type Error = BigFixedError;
+                fn try_from(x: &BigFixed) -> Result<$int, BigFixedError> {
+                    let mut formatted = x.clone();
+                    formatted.format()?;
+                    if formatted.position < 0isize {
+                        return Err(BigFixedIntError(Fractional));
                     }
+                    if x > &BigFixed::from(<$int>::MAX) || x < &BigFixed::from(<$int>::MIN) {
+                        return Err(BigFixedIntError(Overflow));
+                    }
+                    Ok(x.[<to_ $int _wrapping>]())
                 }
             }
         }
     };
 }
 
-to_signed_int!(isize, usize, SIZEBYTES);
-to_signed_int!(i8, u8, 1);
-to_signed_int!(i16, u16, 2);
-to_signed_int!(i32, u32, 4);
-to_signed_int!(i64, u64, 8);
-to_signed_int!(i128, u128, 16);
+to_int!(usize, SIZEBYTES);
+to_int!(isize, SIZEBYTES);
+to_int!(u8, 1);
+to_int!(i8, 1);
+to_int!(u16, 2);
+to_int!(i16, 2);
+to_int!(u32, 4);
+to_int!(i32, 4);
+to_int!(u64, 8);
+to_int!(i64, 8);
+to_int!(u128, 16);
+to_int!(i128, 16);
+
+// infinities and NaNs fail with a FloatError
 
 macro_rules! from_float {
     ($type: ty, $exponent_len: expr, $exponent_bias: expr, $significand_len: expr) => {
-        impl From<$type> for BigFixed {
-            fn from(x: $type) -> BigFixed {
-                let mut returner = BigFixed::from(x.to_bits());
-                returner = returner.float_from_bits($exponent_len, $exponent_bias, $significand_len).unwrap();
-                returner
+        impl TryFrom<$type> for BigFixed {
+            type Error = BigFixedError;
+            fn try_from(x: $type) -> Result<BigFixed, BigFixedError> {
+                BigFixed::from(x.to_bits()).float_from_bits($exponent_len, $exponent_bias, $significand_len)
             }
         }
     };
@@ -241,14 +284,16 @@ macro_rules! from_float {
 from_float!(f32, 8, 127, 23);
 from_float!(f64, 11, 1023, 52);
 
+// to_float rounds to nearest
+
 macro_rules! to_float {
-    ($type: ty, $unsigned_type: ty, $exponent_len: expr, $exponent_bias: expr, $significand_len: expr) => {
+    ($type: ty, $unsigned_type: ident, $exponent_len: expr, $exponent_bias: expr, $significand_len: expr) => {
         impl From<BigFixed> for $type {
             fn from(mut x: BigFixed) -> $type {
-                x = x.float_to_bits($exponent_len, $exponent_bias, $significand_len).unwrap();
-                <$type>::from_bits(
-                    <$unsigned_type>::from(&x)
-                )
+                x = x.float_to_bits($exponent_len, $exponent_bias, $significand_len, Rounding::Round).unwrap();
+                paste! {
+                    <$type>::from_bits(x.[<to_ $unsigned_type _wrapping>]())
+                }
             }
         }
 
@@ -262,3 +307,65 @@ macro_rules! to_float {
 
 to_float!(f32, u32, 8, 127, 23);
 to_float!(f64, u64, 11, 1023, 52);
+
+// Raw bit patterns of other IEEE 754 style formats, rounding to nearest. There is no native Rust type for these so the bits go in and out as
+// unsigned integers.
+
+macro_rules! float_bits {
+    ($from: ident, $to: ident, $bits: ident, $exponent_len: expr, $exponent_bias: expr, $significand_len: expr) => {
+        impl BigFixed {
+            pub fn $from(bits: $bits) -> Result<BigFixed, BigFixedError> {
+                BigFixed::from(bits).float_from_bits($exponent_len, $exponent_bias, $significand_len)
+            }
+
+            pub fn $to(&self) -> Result<$bits, BigFixedError> {
+                let bits = self.clone().float_to_bits($exponent_len, $exponent_bias, $significand_len, Rounding::Round)?;
+                paste! {
+                    Ok(bits.[<to_ $bits _wrapping>]())
+                }
+            }
+        }
+    };
+}
+
+// IEEE binary16
+float_bits!(from_f16_bits, to_f16_bits, u16, 5, 15, 10);
+// bfloat16, the high half of an f32
+float_bits!(from_bf16_bits, to_bf16_bits, u16, 8, 127, 7);
+// IEEE binary128
+float_bits!(from_f128_bits, to_f128_bits, u128, 15, 16383, 112);
+
+// x87 80 bit extended precision, little endian as stored in memory: 64 significand bits including an explicit integer bit, then 15 exponent bits
+// and the sign. Dropping the integer bit leaves the usual implicit format with a 63 bit significand. The explicit bit allows patterns the implicit
+// format cannot express. A zero exponent with the integer bit set (pseudo-denormal) is read as the x87 does, with the denormal exponent 1 - bias.
+// A nonzero exponent without it (unnormal, pseudo-infinity, pseudo-NaN) is an invalid operand since the 387 and gives NaN.
+const X87_INTEGER_BIT: u128 = 1 << 63;
+
+impl BigFixed {
+    pub fn from_x87_bits(bytes: [u8; 10]) -> Result<BigFixed, BigFixedError> {
+        let mut wide = [0u8; 16];
+        wide[..10].copy_from_slice(&bytes);
+        let bits = u128::from_le_bytes(wide);
+        let sign_exponent = bits >> 64;
+        let integer_bit = bits & X87_INTEGER_BIT != 0;
+        let sign_exponent = match (sign_exponent & 0x7fff != 0, integer_bit) {
+            (true, false) => return Err(BigFixedFloatError(NaN)),
+            // a pseudo-denormal has the value of the same significand with exponent 1
+            (false, true) => sign_exponent | 1,
+            _ => sign_exponent
+        };
+        let implicit = (sign_exponent << 63) | (bits & (X87_INTEGER_BIT - 1));
+        BigFixed::from(implicit).float_from_bits(15, 16383, 63)
+    }
+
+    pub fn to_x87_bits(&self) -> Result<[u8; 10], BigFixedError> {
+        let implicit = self.clone().float_to_bits(15, 16383, 63, Rounding::Round)?.to_u128_wrapping();
+        let sign_exponent = implicit >> 63;
+        // the integer bit is set for everything but zero exponents
+        let integer_bit = if sign_exponent & 0x7fff != 0 {X87_INTEGER_BIT} else {0};
+        let bits = (sign_exponent << 64) | integer_bit | (implicit & (X87_INTEGER_BIT - 1));
+        let mut bytes = [0u8; 10];
+        bytes.copy_from_slice(&bits.to_le_bytes()[..10]);
+        Ok(bytes)
+    }
+}
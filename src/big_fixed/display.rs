@@ -0,0 +1,293 @@
+/*
+    Decimal formatting. Every BigFixed has a terminating decimal expansion: if |x| = m * 2^-b with m odd then x = m * 5^b / 10^b which has exactly b
+    decimal places. Without a precision Display prints all of them. With {:.N} the value is rounded to N decimal places under a Rounding (Round unless
+    chosen through display_rounded) by cutting off x * 10^N at the integer position. Width, fill, alignment, + and 0 are honored as for integers.
+
+    Scientific notation ({:e}, {:E}) works the same way with the precision counting digits after the leading one. A rounded x * 10^k is computed
+    directly from the binary magnitude with from_magnitude_pow10 so only the requested digits are ever produced; finding the decimal exponent takes a
+    couple of truncated (Floor) probes around an estimate from the bit length.
+*/
+
+use crate::{Index, Cutoff, Rounding, BigFixed, BigFixedError, BigFixedParseError, big_fixed::{magnitude::*, parse::NonTerminating}};
+
+use core::{fmt, iter::repeat_n, cmp::min};
+
+use alloc::{string::String, format};
+
+// Display adapter for BigFixed which rounds {:.N}, {:.Ne} and {:.NE} with a chosen Rounding
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayRounded<'a> {
+    value: &'a BigFixed,
+    round: Rounding
+}
+
+impl BigFixed {
+    pub fn display_rounded(&self, round: Rounding) -> DisplayRounded<'_> {
+        DisplayRounded {
+            value: self,
+            round
+        }
+    }
+
+    // self * 10^pow10 rounded to an integer
+    pub fn round_pow10(&self, pow10: isize, round: Rounding) -> Result<BigFixed, BigFixedError> {
+        self.round_pow(10, pow10, round)
+    }
+
+    // decimal digits of the absolute value of an integer
+    fn integer_digits(&self) -> Result<String, BigFixedError> {
+        let (mut mag, bit) = self.magnitude()?;
+        shl_bits(&mut mag, Index::saturating_unsigned(bit));
+        Ok(to_decimal_string(mag))
+    }
+
+    // Decimal digits of the value rounded to the given number of places (all of them if None) along with whether the rounded value is negative.
+    // The returned digits have the decimal point inserted but no sign.
+    pub fn to_decimal_places(&self, places: Option<usize>, round: Rounding) -> Result<(String, bool), BigFixedError> {
+        let (mut mag, bit) = self.magnitude()?;
+        let exact_places = Index::saturating_unsigned(-bit);
+        let places = places.unwrap_or(exact_places);
+        let (digits, neg) = if places >= exact_places {
+            // exact, scale the magnitude up to an integer with exact_places decimal places
+            if bit >= 0 {
+                shl_bits(&mut mag, Index::uncastsize(bit)?);
+            } else {
+                mul_pow(&mut mag, &[5], exact_places);
+            }
+            (to_decimal_string(mag), self.is_neg())
+        } else {
+            let scaled = self.round_pow10(Index::castsize(places)?, round)?;
+            (scaled.integer_digits()?, scaled.is_neg())
+        };
+        let written_places = min(places, exact_places);
+        let mut returner = String::with_capacity(digits.len() + places + 2);
+        if digits.len() > written_places {
+            returner.push_str(&digits[..(digits.len() - written_places)]);
+        } else {
+            returner.push('0');
+        }
+        if places > 0 {
+            returner.push('.');
+            returner.extend(repeat_n('0', written_places.saturating_sub(digits.len())));
+            returner.push_str(&digits[digits.len().saturating_sub(written_places)..]);
+            returner.extend(repeat_n('0', places - written_places));
+        }
+        Ok((returner, neg))
+    }
+
+    // floor(log10(|self|)), or None for zero
+    pub fn decimal_exponent(&self) -> Result<Option<isize>, BigFixedError> {
+        let (mag, bit) = self.magnitude()?;
+        if mag.is_empty() {
+            return Ok(None);
+        }
+        // floor(log2(|self|)) * log10(2), off by at most one for any reasonable size
+        let log2 = (Index::castsize(bit_len(&mag))? - 1 + bit) as i128;
+        let mut exponent = (log2 * 30103).div_euclid(100000) as isize;
+        let ten = BigFixed::from(10);
+        loop {
+            let probe = BigFixed::from_magnitude_pow10(false, mag.clone(), bit, -exponent, Cutoff::INTEGER)?.int()?;
+            if probe.is_zero() {
+                exponent -= 1;
+            } else if probe >= ten {
+                exponent += 1;
+            } else {
+                return Ok(Some(exponent));
+            }
+        }
+    }
+
+    // The significand digits d.ddd (all of the exact ones if digits is None, otherwise exactly that many after the point) with the decimal exponent
+    // and whether the rounded value is negative.
+    pub fn to_scientific(&self, digits: Option<usize>, round: Rounding) -> Result<(String, isize, bool), BigFixedError> {
+        if self.is_zero() {
+            let mut returner = String::from("0");
+            if let Some(digits) = digits {
+                if digits > 0 {
+                    returner.push('.');
+                    returner.extend(repeat_n('0', digits));
+                }
+            }
+            return Ok((returner, 0, false));
+        }
+        let (significand, mut exponent, neg) = match digits {
+            None => {
+                let (mut mag, bit) = self.magnitude()?;
+                let mut s;
+                let exponent;
+                if bit >= 0 {
+                    shl_bits(&mut mag, Index::uncastsize(bit)?);
+                    s = to_decimal_string(mag);
+                    exponent = Index::castsize(s.len())? - 1;
+                    s.truncate(s.trim_end_matches('0').len());
+                } else {
+                    mul_pow(&mut mag, &[5], Index::uncastsize(-bit)?);
+                    s = to_decimal_string(mag);
+                    exponent = Index::castsize(s.len())? - 1 + bit;
+                }
+                (s, exponent, self.is_neg())
+            },
+            Some(digits) => {
+                // zero returned early
+                let exponent = self.decimal_exponent()?.unwrap_or(0);
+                let scaled = self.round_pow10(Index::castsize(digits)? - exponent, round)?;
+                (scaled.integer_digits()?, exponent, scaled.is_neg())
+            }
+        };
+        let mut significand = significand.as_str();
+        if let Some(digits) = digits {
+            if significand.len() > digits + 1 {
+                // rounding carried into a new digit, which means the significand is 1000...
+                exponent += 1;
+                significand = &significand[..(digits + 1)];
+            }
+        }
+        let mut returner = String::with_capacity(significand.len() + 1);
+        returner.push_str(&significand[..1]);
+        if significand.len() > 1 {
+            returner.push('.');
+            returner.push_str(&significand[1..]);
+        }
+        Ok((returner, exponent, neg))
+    }
+
+    // Whether d * 10^pow10 parses back to target under the cutoff, exactly as parse_c would treat the literal but without going through a string
+    fn decimal_round_trips(d: &BigFixed, pow10: isize, target: &BigFixed, cutoff: Cutoff) -> Result<bool, BigFixedError> {
+        let (mag, bit) = d.magnitude()?;
+        let mut parsed = match BigFixed::from_magnitude_pow10(d.is_neg(), mag, bit, pow10, cutoff) {
+            Ok(parsed) => parsed,
+            Err(BigFixedParseError(NonTerminating)) => return Ok(false),
+            Err(e) => return Err(e)
+        };
+        parsed.cutoff(cutoff)?;
+        Ok(parsed == *target)
+    }
+
+    // The n significant digit rounding of target, as an integer d with target ~ d * 10^(exponent + 1 - n), which parses back to target: the nearest
+    // if it does and otherwise whichever of the two directed roundings does.
+    fn round_trip_digits(target: &BigFixed, exponent: isize, n: usize, cutoff: Cutoff) -> Result<Option<BigFixed>, BigFixedError> {
+        let pow10 = exponent + 1 - Index::castsize(n)?;
+        for round in [Rounding::Round, Rounding::Floor, Rounding::Ceiling] {
+            let d = target.round_pow10(-pow10, round)?;
+            if BigFixed::decimal_round_trips(&d, pow10, target, cutoff)? {
+                return Ok(Some(d));
+            }
+        }
+        Ok(None)
+    }
+
+    // The shortest decimal string which parse_c turns back into self under the cutoff, preferring the nearest one when several have the same length.
+    // Self is cut off first since nothing else can round trip. Decimal exponents in -7..21 are written positionally and anything else in scientific
+    // notation, like 1.2345e-300.
+    //
+    // The decimals which parse back to the target form an interval around it. Shortening the n digit floor or ceiling of the target only moves it
+    // away from the target, so if some n digit rounding round trips then so does one with n + 1 digits, and the shortest length can be found by
+    // binary search between 1 and the length of the exact expansion. Candidates are checked by building d * 10^k directly instead of parsing text.
+    pub fn to_shortest_decimal(&self, cutoff: Cutoff) -> Result<String, BigFixedError> {
+        let (digits, neg) = self.shortest_decimal_parts(cutoff)?;
+        Ok(if neg {format!("-{}", digits)} else {digits})
+    }
+
+    // to_shortest_decimal without the sign, along with whether it is negative
+    pub(crate) fn shortest_decimal_parts(&self, cutoff: Cutoff) -> Result<(String, bool), BigFixedError> {
+        let mut target = self.clone();
+        target.cutoff(cutoff)?;
+        let exponent = match target.decimal_exponent()? {
+            Some(exponent) => exponent,
+            None => return Ok((String::from("0"), false))
+        };
+        // an odd m * 2^bit with bit < 0 has its last nonzero decimal digit at 10^bit; integers are exact with all of their digits
+        let (_, bit) = target.magnitude()?;
+        let exact_len = Index::uncastsize(exponent + 1 - min(bit, 0))?;
+        let (mut low, mut high) = (1, exact_len);
+        while low < high {
+            let n = low + (high - low) / 2;
+            if BigFixed::round_trip_digits(&target, exponent, n, cutoff)?.is_some() {
+                high = n;
+            } else {
+                low = n + 1;
+            }
+        }
+        let d = match BigFixed::round_trip_digits(&target, exponent, low, cutoff)? {
+            Some(d) => d,
+            // the exact expansion always parses back
+            None => target.round_pow10(Index::castsize(exact_len)? - 1 - exponent, Rounding::Round)?
+        };
+        let digits = d.integer_digits()?;
+        // rounding up can carry into a new leading digit, as 9.96 to 10
+        let exponent = exponent + Index::castsize(digits.len())? - Index::castsize(low)?;
+        let digits = digits.trim_end_matches('0');
+        let mut returner = String::new();
+        if (-7..21).contains(&exponent) {
+            if exponent >= 0 {
+                let int_len = Index::uncastsize(exponent)? + 1;
+                if digits.len() <= int_len {
+                    returner.push_str(digits);
+                    returner.extend(repeat_n('0', int_len - digits.len()));
+                } else {
+                    returner.push_str(&digits[..int_len]);
+                    returner.push('.');
+                    returner.push_str(&digits[int_len..]);
+                }
+            } else {
+                returner.push_str("0.");
+                returner.extend(repeat_n('0', Index::uncastsize(-exponent)? - 1));
+                returner.push_str(digits);
+            }
+        } else {
+            returner.push_str(&digits[..1]);
+            if digits.len() > 1 {
+                returner.push('.');
+                returner.push_str(&digits[1..]);
+            }
+            returner.push_str(&format!("e{}", exponent));
+        }
+        Ok((returner, d.is_neg()))
+    }
+
+    pub(crate) fn fmt_decimal(&self, f: &mut fmt::Formatter, round: Rounding) -> fmt::Result {
+        let (digits, neg) = self.to_decimal_places(f.precision(), round).map_err(|_| fmt::Error)?;
+        f.pad_integral(!neg, "", &digits)
+    }
+
+    pub(crate) fn fmt_scientific(&self, f: &mut fmt::Formatter, round: Rounding, e: char) -> fmt::Result {
+        let (significand, exponent, neg) = self.to_scientific(f.precision(), round).map_err(|_| fmt::Error)?;
+        f.pad_integral(!neg, "", &format!("{}{}{}", significand, e, exponent))
+    }
+}
+
+impl fmt::Display for DisplayRounded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_decimal(f, self.round)
+    }
+}
+
+impl fmt::LowerExp for DisplayRounded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_scientific(f, self.round, 'e')
+    }
+}
+
+impl fmt::UpperExp for DisplayRounded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_scientific(f, self.round, 'E')
+    }
+}
+
+impl fmt::Display for BigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_decimal(f, Rounding::Round)
+    }
+}
+
+impl fmt::LowerExp for BigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_scientific(f, Rounding::Round, 'e')
+    }
+}
+
+impl fmt::UpperExp for BigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_scientific(f, Rounding::Round, 'E')
+    }
+}
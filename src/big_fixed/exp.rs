@@ -7,7 +7,6 @@ impl BigFixed {
     }
 
     pub fn taylor_exp(&self, precision: Index) -> Result<BigFixed, BigFixedError> {
-        println!("exp {} to {}", self, precision);
         // go until x^n/n! < s where x = self, s = 2^precision
         // x^n < n!*s
         let mut n = BigFixed::from(1);
@@ -23,13 +22,11 @@ impl BigFixed {
                 for _i in 0..p {
                     powten *= 10;
                 }
-                println!("{}", powten);
             }
             n.increment()?;
             xn *= self;
             nfs *= &n;
         }
-        println!("found n {}", n);
         Ok(BigFixed::from(0))
     }
 }
@@ -1,6 +1,6 @@
 use crate::{digit::*, Index as Indx, BigFixed};
 
-use std::{ops::{Index, IndexMut}};
+use core::{ops::{Index, IndexMut}};
 
 impl Index<Indx> for BigFixed {
     type Output = Digit;
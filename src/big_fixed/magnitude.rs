@@ -0,0 +1,752 @@
+/*
+    Kernels on unsigned little endian magnitudes (a plain Vec<Digit> with no head or position) used by the radix conversions. Working on magnitudes
+    sidesteps two's complement bookkeeping when repeatedly scaling by small numbers like 10 or 5. Large operands go through Karatsuba multiplication
+    and division by a Newton reciprocal so that converting n digits to or from another radix by divide and conquer costs O(n^1.6 log n) rather than
+    the O(n^2) of peeling off one small digit at a time.
+
+    from_magnitude_pow10 builds m * 2^b * 10^k. Nonnegative k is exact. Negative k = -j gives (m / 5^j) * 2^(b-j) which is exact iff 5^j divides m;
+    otherwise the binary expansion never terminates and the result is rounded to the cutoff. Correct rounding is done with a sticky bit: let
+    q = floor(m * 2^b * 10^k * 2^s) and let sticky be whether anything was lost. The number (2q + sticky) * 2^-(s + 1) agrees with the true value on
+    every bit at or above 2^-s and is nonzero below 2^-s exactly when the true value is, so as long as the cutoff index lies strictly above 2^-s every
+    rounding mode treats the two the same.
+*/
+
+use crate::{digit::*, Index, Cutoff, BigFixed, BigFixedError, BigFixedParseError, ParseError, AdditionOverflow, big_fixed::parse::MAX_PARSE_BITS, MultiplicationOverflow};
+
+use core::{iter::repeat_n, cmp::{max, min, Ordering}};
+
+use alloc::{vec::Vec, vec, string::String, collections::BTreeMap};
+
+// operand sizes in digits above which multiplication switches to Karatsuba and division to Newton's reciprocal
+pub(crate) const KARATSUBA_THRESHOLD: usize = 32;
+pub(crate) const NEWTON_THRESHOLD: usize = 48;
+
+// mag = mag * mul + add
+pub(crate) fn mul_small_add(mag: &mut Vec<Digit>, mul: Digit, add: Digit) {
+    let mut carry = add as DoubleDigit;
+    for d in mag.iter_mut() {
+        let res = (*d as DoubleDigit) * (mul as DoubleDigit) + carry;
+        *d = res as Digit;
+        carry = res >> DIGITBITS;
+    }
+    if carry > 0 {
+        mag.push(carry as Digit);
+    }
+}
+
+// mag = floor(mag / div), returning the remainder
+pub(crate) fn div_small(mag: &mut Vec<Digit>, div: Digit) -> Digit {
+    let div = div as DoubleDigit;
+    let mut rem: DoubleDigit = 0;
+    for d in mag.iter_mut().rev() {
+        let cur = (rem << DIGITBITS) | (*d as DoubleDigit);
+        *d = (cur / div) as Digit;
+        rem = cur % div;
+    }
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+    rem as Digit
+}
+
+// mag = mag * 2^amount
+pub(crate) fn shl_bits(mag: &mut Vec<Digit>, amount: usize) {
+    let positions = amount / DIGITBITS;
+    let bits = amount % DIGITBITS;
+    if bits > 0 {
+        let mut carry: Digit = 0;
+        for d in mag.iter_mut() {
+            let next = *d >> (DIGITBITS - bits);
+            *d = (*d << bits) | carry;
+            carry = next;
+        }
+        if carry > 0 {
+            mag.push(carry);
+        }
+    }
+    if positions > 0 && !mag.is_empty() {
+        mag.splice(0..0, repeat_n(0, positions));
+    }
+}
+
+// mag = floor(mag / 2^amount), returning whether any nonzero bits were dropped
+pub(crate) fn shr_bits(mag: &mut Vec<Digit>, amount: usize) -> bool {
+    let positions = amount / DIGITBITS;
+    let bits = amount % DIGITBITS;
+    if positions >= mag.len() {
+        let sticky = mag.iter().any(|&d| d != 0);
+        mag.clear();
+        return sticky;
+    }
+    let mut sticky = mag.drain(0..positions).any(|d| d != 0);
+    if bits > 0 {
+        sticky |= mag[0] & !(ALLONES << bits) != 0;
+        for i in 0..mag.len() {
+            let carry = if i + 1 < mag.len() {mag[i + 1] << (DIGITBITS - bits)} else {0};
+            mag[i] = (mag[i] >> bits) | carry;
+        }
+    }
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+    sticky
+}
+
+// number of significant bits in mag
+pub(crate) fn bit_len(mag: &[Digit]) -> usize {
+    match mag.last() {
+        Some(high) => mag.len() * DIGITBITS - high.leading_zeros() as usize,
+        None => 0
+    }
+}
+
+// greatest power of base which fits in a Digit along with its exponent
+pub(crate) fn pow_chunk(base: Digit) -> (Digit, usize) {
+    let mut pow: DoubleDigit = 1;
+    let mut exp = 0;
+    while pow * (base as DoubleDigit) <= ALLONES as DoubleDigit {
+        pow *= base as DoubleDigit;
+        exp += 1;
+    }
+    (pow as Digit, exp)
+}
+
+// mag = mag * base^exp
+pub(crate) fn mul_pow(mag: &mut Vec<Digit>, base: &[Digit], exp: usize) {
+    let base = significant(base);
+    if base == [1] || exp == 0 {
+        return;
+    }
+    if base.len() > 1 || exp / pow_chunk(base[0]).1 > KARATSUBA_THRESHOLD {
+        *mag = mul_mag(mag, &pow_mag(base, exp));
+        return;
+    }
+    let (chunk, chunk_exp) = pow_chunk(base[0]);
+    let mut exp = exp;
+    while exp > 0 {
+        let step = if exp >= chunk_exp {chunk} else {base[0].pow(exp as u32)};
+        mul_small_add(mag, step, 0);
+        exp -= exp.min(chunk_exp);
+    }
+}
+
+// mag = floor(mag / base^exp), returning whether there was a remainder
+pub(crate) fn div_pow(mag: &mut Vec<Digit>, base: &[Digit], exp: usize) -> bool {
+    let base = significant(base);
+    if base == [1] || exp == 0 {
+        return false;
+    }
+    if base.len() > 1 || exp / pow_chunk(base[0]).1 > KARATSUBA_THRESHOLD {
+        let (quot, rem) = divrem_mag(mag, &pow_mag(base, exp));
+        *mag = quot;
+        return !rem.is_empty();
+    }
+    let (chunk, chunk_exp) = pow_chunk(base[0]);
+    let mut exp = exp;
+    let mut sticky = false;
+    while exp > 0 {
+        let step = if exp >= chunk_exp {chunk} else {base[0].pow(exp as u32)};
+        sticky |= div_small(mag, step) != 0;
+        exp -= exp.min(chunk_exp);
+    }
+    sticky
+}
+
+// the magnitude of a native integer
+pub(crate) fn mag_from_u128(mut x: u128) -> Vec<Digit> {
+    let mut returner = vec![];
+    while x > 0 {
+        returner.push(x as Digit);
+        x = x.checked_shr(DIGITBITS as u32).unwrap_or(0);
+    }
+    returner
+}
+
+// mag without its high zero digits
+pub(crate) fn significant(mag: &[Digit]) -> &[Digit] {
+    let mut len = mag.len();
+    while len > 0 && mag[len - 1] == 0 {
+        len -= 1;
+    }
+    &mag[..len]
+}
+
+pub(crate) fn trim(mag: &mut Vec<Digit>) {
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+}
+
+pub(crate) fn cmp_mag(a: &[Digit], b: &[Digit]) -> Ordering {
+    let a = significant(a);
+    let b = significant(b);
+    if a.len() != b.len() {
+        return a.len().cmp(&b.len());
+    }
+    for i in (0..a.len()).rev() {
+        if a[i] != b[i] {
+            return a[i].cmp(&b[i]);
+        }
+    }
+    Ordering::Equal
+}
+
+// a = a + b * 2^(DIGITBITS * shift)
+pub(crate) fn add_mag(a: &mut Vec<Digit>, b: &[Digit], shift: usize) {
+    let b = significant(b);
+    if a.len() < b.len() + shift {
+        a.resize(b.len() + shift, 0);
+    }
+    let mut carry: DoubleDigit = 0;
+    for i in 0..b.len() {
+        let res = (a[i + shift] as DoubleDigit) + (b[i] as DoubleDigit) + carry;
+        a[i + shift] = res as Digit;
+        carry = res >> DIGITBITS;
+    }
+    let mut i = b.len() + shift;
+    while carry > 0 {
+        if i == a.len() {
+            a.push(carry as Digit);
+            break;
+        }
+        let res = (a[i] as DoubleDigit) + carry;
+        a[i] = res as Digit;
+        carry = res >> DIGITBITS;
+        i += 1;
+    }
+}
+
+// a = a - b * 2^(DIGITBITS * shift), which must not be negative
+pub(crate) fn sub_mag(a: &mut Vec<Digit>, b: &[Digit], shift: usize) {
+    let b = significant(b);
+    let mut borrow = false;
+    for i in 0..b.len() {
+        let (diff, o1) = a[i + shift].overflowing_sub(b[i]);
+        let (diff, o2) = diff.overflowing_sub(borrow as Digit);
+        a[i + shift] = diff;
+        borrow = o1 || o2;
+    }
+    let mut i = b.len() + shift;
+    while borrow {
+        let (diff, o) = a[i].overflowing_sub(1);
+        a[i] = diff;
+        borrow = o;
+        i += 1;
+    }
+    trim(a);
+}
+
+fn mul_schoolbook(a: &[Digit], b: &[Digit]) -> Vec<Digit> {
+    let mut returner = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0 {
+            continue;
+        }
+        let mut carry: DoubleDigit = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let res = (x as DoubleDigit) * (y as DoubleDigit) + (returner[i + j] as DoubleDigit) + carry;
+            returner[i + j] = res as Digit;
+            carry = res >> DIGITBITS;
+        }
+        returner[i + b.len()] = carry as Digit;
+    }
+    trim(&mut returner);
+    returner
+}
+
+// a * b, by Karatsuba above KARATSUBA_THRESHOLD digits
+pub(crate) fn mul_mag(a: &[Digit], b: &[Digit]) -> Vec<Digit> {
+    let a = significant(a);
+    let b = significant(b);
+    let (a, b) = if a.len() >= b.len() {(a, b)} else {(b, a)};
+    if b.len() < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+    let half = a.len().div_ceil(2);
+    let mut returner;
+    if b.len() <= half {
+        // unbalanced, only split the longer one
+        returner = mul_mag(&a[..half], b);
+        add_mag(&mut returner, &mul_mag(&a[half..], b), half);
+    } else {
+        let (a0, a1) = a.split_at(half);
+        let (b0, b1) = b.split_at(half);
+        let low = mul_mag(a0, b0);
+        let high = mul_mag(a1, b1);
+        let mut a_sum = a0.to_vec();
+        add_mag(&mut a_sum, a1, 0);
+        let mut b_sum = b0.to_vec();
+        add_mag(&mut b_sum, b1, 0);
+        let mut middle = mul_mag(&a_sum, &b_sum);
+        sub_mag(&mut middle, &low, 0);
+        sub_mag(&mut middle, &high, 0);
+        returner = low;
+        add_mag(&mut returner, &middle, half);
+        add_mag(&mut returner, &high, 2*half);
+    }
+    trim(&mut returner);
+    returner
+}
+
+// The columns of a * b from skip up, that is the sum of a[i] * b[j] * 2^(DIGITBITS * (i + j - skip)) over i + j >= skip. The columns left out add
+// less than min(a.len(), b.len()) * 2^(DIGITBITS * (skip + 1)) to the full product, which bounds the error of the short product.
+pub(crate) fn mul_mag_high(a: &[Digit], b: &[Digit], skip: usize) -> Vec<Digit> {
+    if a.len() + b.len() <= skip {
+        return vec![];
+    }
+    let mut returner = vec![0; a.len() + b.len() - skip];
+    for (i, &x) in a.iter().enumerate() {
+        let start = skip.saturating_sub(i);
+        if x == 0 || start >= b.len() {
+            continue;
+        }
+        let mut carry: DoubleDigit = 0;
+        for (j, &y) in b.iter().enumerate().skip(start) {
+            let res = (x as DoubleDigit) * (y as DoubleDigit) + (returner[i + j - skip] as DoubleDigit) + carry;
+            returner[i + j - skip] = res as Digit;
+            carry = res >> DIGITBITS;
+        }
+        returner[i + b.len() - skip] = carry as Digit;
+    }
+    trim(&mut returner);
+    returner
+}
+
+// base^exp by repeated squaring
+pub(crate) fn pow_mag(base: &[Digit], mut exp: usize) -> Vec<Digit> {
+    let mut returner = vec![1];
+    let mut square = significant(base).to_vec();
+    loop {
+        if exp & 1 == 1 {
+            returner = mul_mag(&returner, &square);
+        }
+        exp >>= 1;
+        if exp == 0 {
+            return returner;
+        }
+        square = mul_mag(&square, &square);
+    }
+}
+
+// (floor(n / d), n mod d) by long division (Knuth's algorithm D), d nonzero
+fn divrem_schoolbook(n: &[Digit], d: &[Digit]) -> (Vec<Digit>, Vec<Digit>) {
+    let n = significant(n);
+    let d = significant(d);
+    if cmp_mag(n, d) == Ordering::Less {
+        return (vec![], n.to_vec());
+    }
+    if d.len() == 1 {
+        let mut quot = n.to_vec();
+        let rem = div_small(&mut quot, d[0]);
+        return (quot, if rem == 0 {vec![]} else {vec![rem]});
+    }
+    // normalize so the divisor's greatest bit is set, which keeps each estimated quotient digit within 2 of the truth
+    let shift = d[d.len() - 1].leading_zeros() as usize;
+    let mut v = d.to_vec();
+    shl_bits(&mut v, shift);
+    let mut u = n.to_vec();
+    shl_bits(&mut u, shift);
+    u.resize(n.len() + 1, 0);
+    let m = v.len();
+    let radix: DoubleDigit = 1 << DIGITBITS;
+    let mut quot = vec![0; n.len() - m + 1];
+    for j in (0..=(n.len() - m)).rev() {
+        let top = ((u[j + m] as DoubleDigit) << DIGITBITS) | (u[j + m - 1] as DoubleDigit);
+        let mut qhat = top / (v[m - 1] as DoubleDigit);
+        let mut rhat = top % (v[m - 1] as DoubleDigit);
+        while qhat >= radix || qhat * (v[m - 2] as DoubleDigit) > ((rhat << DIGITBITS) | (u[j + m - 2] as DoubleDigit)) {
+            qhat -= 1;
+            rhat += v[m - 1] as DoubleDigit;
+            if rhat >= radix {
+                break;
+            }
+        }
+        // u = u - qhat * v * radix^j
+        let mut borrow: SignedDoubleDigit = 0;
+        for i in 0..m {
+            let prod = qhat * (v[i] as DoubleDigit);
+            let diff = (u[i + j] as SignedDoubleDigit) - borrow - ((prod & (ALLONES as DoubleDigit)) as SignedDoubleDigit);
+            u[i + j] = diff as Digit;
+            borrow = ((prod >> DIGITBITS) as SignedDoubleDigit) - (diff >> DIGITBITS);
+        }
+        let diff = (u[j + m] as SignedDoubleDigit) - borrow;
+        u[j + m] = diff as Digit;
+        if diff < 0 {
+            // qhat was one too large, add v back
+            qhat -= 1;
+            let mut carry: DoubleDigit = 0;
+            for i in 0..m {
+                let res = (u[i + j] as DoubleDigit) + (v[i] as DoubleDigit) + carry;
+                u[i + j] = res as Digit;
+                carry = res >> DIGITBITS;
+            }
+            u[j + m] = u[j + m].wrapping_add(carry as Digit);
+        }
+        quot[j] = qhat as Digit;
+    }
+    trim(&mut quot);
+    u.truncate(m);
+    shr_bits(&mut u, shift);
+    (quot, u)
+}
+
+// floor(radix^(2m) / d) for d of m digits with its greatest bit set. Newton's iteration x = x + x * (radix^(2m) - d * x) / radix^(2m) doubles
+// the number of correct digits, so it starts from the reciprocal of the high half of d and finishes with a few unit corrections.
+fn reciprocal(d: &[Digit]) -> Vec<Digit> {
+    let m = d.len();
+    let mut target = vec![0; 2*m];
+    target.push(1);
+    if m <= NEWTON_THRESHOLD {
+        return divrem_schoolbook(&target, d).0;
+    }
+    let h = m.div_ceil(2);
+    let mut x = reciprocal(&d[(m - h)..]);
+    x.splice(0..0, repeat_n(0, m - h));
+    let dx = mul_mag(d, &x);
+    if cmp_mag(&dx, &target) != Ordering::Greater {
+        let mut err = target.clone();
+        sub_mag(&mut err, &dx, 0);
+        let step = mul_mag(&x, &err);
+        add_mag(&mut x, &step[min(2*m, step.len())..], 0);
+    } else {
+        let mut err = dx;
+        sub_mag(&mut err, &target, 0);
+        let step = mul_mag(&x, &err);
+        sub_mag(&mut x, &step[min(2*m, step.len())..], 0);
+    }
+    let mut dx = mul_mag(d, &x);
+    while cmp_mag(&dx, &target) == Ordering::Greater {
+        sub_mag(&mut x, &[1], 0);
+        sub_mag(&mut dx, d, 0);
+    }
+    let mut rem = target;
+    sub_mag(&mut rem, &dx, 0);
+    while cmp_mag(&rem, d) != Ordering::Less {
+        add_mag(&mut x, &[1], 0);
+        sub_mag(&mut rem, d, 0);
+    }
+    x
+}
+
+// A divisor prepared for repeated division: normalized so its greatest bit is set and paired with its reciprocal so that each division costs
+// two multiplications.
+pub(crate) struct Divisor {
+    normalized: Vec<Digit>,
+    shift: usize,
+    reciprocal: Vec<Digit>
+}
+
+impl Divisor {
+    pub(crate) fn new(d: &[Digit]) -> Divisor {
+        let d = significant(d);
+        assert!(!d.is_empty(), "division by zero");
+        let shift = d[d.len() - 1].leading_zeros() as usize;
+        let mut normalized = d.to_vec();
+        shl_bits(&mut normalized, shift);
+        let reciprocal = reciprocal(&normalized);
+        Divisor {
+            normalized,
+            shift,
+            reciprocal
+        }
+    }
+
+    // for normalized u < radix^(2m) the estimate floor(u * reciprocal / radix^(2m)) is at most 2 below the quotient and never above it
+    fn divrem_normalized(&self, u: &[Digit]) -> (Vec<Digit>, Vec<Digit>) {
+        let m = self.normalized.len();
+        let prod = mul_mag(u, &self.reciprocal);
+        let mut quot = prod[min(2*m, prod.len())..].to_vec();
+        let mut rem = u.to_vec();
+        sub_mag(&mut rem, &mul_mag(&quot, &self.normalized), 0);
+        while cmp_mag(&rem, &self.normalized) != Ordering::Less {
+            sub_mag(&mut rem, &self.normalized, 0);
+            add_mag(&mut quot, &[1], 0);
+        }
+        (quot, rem)
+    }
+
+    // (floor(n / d), n mod d), taking n an m digit chunk at a time so that each partial remainder followed by the next chunk is below radix^(2m)
+    pub(crate) fn divrem(&self, n: &[Digit]) -> (Vec<Digit>, Vec<Digit>) {
+        let m = self.normalized.len();
+        let mut u = significant(n).to_vec();
+        shl_bits(&mut u, self.shift);
+        let mut quot = vec![];
+        let mut rem = vec![];
+        for c in (0..u.len().div_ceil(m)).rev() {
+            let mut cur = u[(c*m)..min((c + 1)*m, u.len())].to_vec();
+            add_mag(&mut cur, &rem, m);
+            let (q, r) = self.divrem_normalized(&cur);
+            add_mag(&mut quot, &q, c*m);
+            rem = r;
+        }
+        trim(&mut quot);
+        shr_bits(&mut rem, self.shift);
+        (quot, rem)
+    }
+}
+
+// (floor(n / d), n mod d), by long division for short divisors or quotients and through the Newton reciprocal otherwise
+pub(crate) fn divrem_mag(n: &[Digit], d: &[Digit]) -> (Vec<Digit>, Vec<Digit>) {
+    let n = significant(n);
+    let d = significant(d);
+    if d.len() <= NEWTON_THRESHOLD || n.len() <= d.len() + NEWTON_THRESHOLD {
+        divrem_schoolbook(n, d)
+    } else {
+        Divisor::new(d).divrem(n)
+    }
+}
+
+// number of digits below which radix conversion is done one small division or multiplication at a time
+const RADIX_THRESHOLD: usize = 2*KARATSUBA_THRESHOLD;
+
+fn radix_fits_digit(radix: u32) -> bool {
+    (radix as u128) <= (ALLONES as u128)
+}
+
+// the low 128 bits of mag
+pub(crate) fn mag_to_u128(mag: &[Digit]) -> u128 {
+    mag.iter().rev().fold(0u128, |acc, &d| (acc << DIGITBITS) | (d as u128))
+}
+
+// the len bits of mag starting at bit low
+pub(crate) fn bit_field(mag: &[Digit], low: usize, len: usize) -> Vec<Digit> {
+    let mut returner = mag.to_vec();
+    shr_bits(&mut returner, low);
+    let positions = len / DIGITBITS;
+    let bits = len % DIGITBITS;
+    if returner.len() > positions {
+        if bits == 0 {
+            returner.truncate(positions);
+        } else {
+            returner.truncate(positions + 1);
+            returner[positions] &= ALLONES >> (DIGITBITS - bits);
+        }
+    }
+    trim(&mut returner);
+    returner
+}
+
+// little endian digits of n in radix appended to out with no high zeros, or zero padded to exactly width digits if given
+fn small_to_radix(mut n: Vec<Digit>, radix: u32, width: Option<usize>, out: &mut Vec<u32>) {
+    let start = out.len();
+    if radix_fits_digit(radix) {
+        let (chunk, chunk_exp) = pow_chunk(radix as Digit);
+        while !n.is_empty() {
+            let mut rem = div_small(&mut n, chunk);
+            for _ in 0..chunk_exp {
+                out.push((rem % (radix as Digit)) as u32);
+                rem /= radix as Digit;
+            }
+        }
+    } else {
+        let radix = mag_from_u128(radix as u128);
+        while !n.is_empty() {
+            let (quot, rem) = divrem_mag(&n, &radix);
+            out.push(mag_to_u128(&rem) as u32);
+            n = quot;
+        }
+    }
+    while out.len() > start && out.last() == Some(&0) {
+        out.pop();
+    }
+    if let Some(width) = width {
+        out.resize(start + width, 0);
+    }
+}
+
+// Divide and conquer: with powers[k] = radix^(2^k) and n < powers[k]^2 split n = q * powers[k] + r and convert r to exactly 2^k digits and q
+// to the rest. Every level divides by the same power so its reciprocal is computed once.
+fn to_radix_rec(n: Vec<Digit>, radix: u32, powers: &[(Vec<Digit>, Option<Divisor>)], k: usize, width: Option<usize>, out: &mut Vec<u32>) {
+    if k == 0 || n.len() <= RADIX_THRESHOLD {
+        small_to_radix(n, radix, width, out);
+        return;
+    }
+    let (power, divisor) = &powers[k];
+    if cmp_mag(&n, power) == Ordering::Less {
+        to_radix_rec(n, radix, powers, k - 1, width, out);
+        return;
+    }
+    let (quot, rem) = match divisor {
+        Some(divisor) => divisor.divrem(&n),
+        None => divrem_mag(&n, power)
+    };
+    let low_width = 1 << k;
+    to_radix_rec(rem, radix, powers, k - 1, Some(low_width), out);
+    to_radix_rec(quot, radix, powers, k - 1, width.map(|w| w - low_width), out);
+}
+
+// little endian digits of n in radix with no high zeros (none at all for zero)
+pub(crate) fn to_radix_digits(n: Vec<Digit>, radix: u32) -> Vec<u32> {
+    let n_bits = bit_len(&n);
+    let mut powers: Vec<(Vec<Digit>, Option<Divisor>)> = vec![(mag_from_u128(radix as u128), None)];
+    while 2*bit_len(&powers[powers.len() - 1].0) - 1 <= n_bits {
+        let square = mul_mag(&powers[powers.len() - 1].0, &powers[powers.len() - 1].0);
+        // the top power divides only once so it is not worth a reciprocal
+        let last = powers.len() - 1;
+        if last > 0 && powers[last].0.len() > NEWTON_THRESHOLD {
+            powers[last].1 = Some(Divisor::new(&powers[last].0));
+        }
+        powers.push((square, None));
+    }
+    let mut returner = Vec::with_capacity((1 << (powers.len() - 1)) * 2);
+    to_radix_rec(n, radix, &powers, powers.len() - 1, None, &mut returner);
+    returner
+}
+
+// the integer with the given little endian digits in radix, combining halves as high * radix^len(low) + low
+pub(crate) fn from_radix_digits(digits: &[u32], radix: u32) -> Vec<Digit> {
+    let mut powers = BTreeMap::new();
+    from_radix_rec(digits, radix, &mut powers)
+}
+
+fn from_radix_rec(digits: &[u32], radix: u32, powers: &mut BTreeMap<usize, Vec<Digit>>) -> Vec<Digit> {
+    if digits.len() <= RADIX_THRESHOLD {
+        let mut returner = vec![];
+        let radix_mag = mag_from_u128(radix as u128);
+        for &d in digits.iter().rev() {
+            if radix_fits_digit(radix) {
+                mul_small_add(&mut returner, radix as Digit, 0);
+            } else {
+                returner = mul_mag(&returner, &radix_mag);
+            }
+            add_mag(&mut returner, &mag_from_u128(d as u128), 0);
+        }
+        trim(&mut returner);
+        return returner;
+    }
+    let half = digits.len() / 2;
+    let low = from_radix_rec(&digits[..half], radix, powers);
+    let high = from_radix_rec(&digits[half..], radix, powers);
+    let power = powers.entry(half).or_insert_with(|| pow_mag(&mag_from_u128(radix as u128), half));
+    let mut returner = mul_mag(&high, power);
+    add_mag(&mut returner, &low, 0);
+    trim(&mut returner);
+    returner
+}
+
+// Fails with TooLarge if mag * radix^pow could take more than MAX_PARSE_BITS bits to build, counting ceil(log2(radix)) bits per unit of pow whatever
+// its sign since a negative pow divides by a power just as large. Power of two radixes only shift and are never too large.
+pub(crate) fn check_pow_bits(mag: &[Digit], radix: u32, pow: isize) -> Result<(), ParseError> {
+    if mag.is_empty() || radix.is_power_of_two() {
+        return Ok(());
+    }
+    let radix_bits = (u32::BITS - (radix - 1).leading_zeros()) as usize;
+    let bits = pow.unsigned_abs().checked_mul(radix_bits).and_then(|b| b.checked_add(bit_len(mag)));
+    match bits {
+        Some(bits) if bits <= MAX_PARSE_BITS => Ok(()),
+        _ => Err(ParseError::TooLarge)
+    }
+}
+
+// decimal digits of mag, most significant first, with no leading zeros ("0" for zero)
+pub(crate) fn to_decimal_string(mag: Vec<Digit>) -> String {
+    let digits = to_radix_digits(mag, 10);
+    if digits.is_empty() {
+        return String::from("0");
+    }
+    digits.iter().rev().map(|&d| char::from(b'0' + d as u8)).collect()
+}
+
+impl BigFixed {
+    // The absolute value as a magnitude m and bit index b so that |self| == m * 2^b. The magnitude is odd unless self is zero.
+    pub(crate) fn magnitude(&self) -> Result<(Vec<Digit>, isize), BigFixedError> {
+        let mut abs = self.abs()?;
+        abs.format()?;
+        if abs.is_zero() {
+            return Ok((vec![], 0));
+        }
+        let mut bit = abs.position.bit_value()?;
+        let shift = abs.body[0].trailing_zeros() as usize;
+        let mut mag = abs.body.into_vec();
+        if shift > 0 {
+            for i in 0..mag.len() {
+                let carry = if i + 1 < mag.len() {mag[i + 1] << (DIGITBITS - shift)} else {0};
+                mag[i] = (mag[i] >> shift) | carry;
+            }
+            if mag.last() == Some(&0) {
+                mag.pop();
+            }
+            bit += Index::castsize(shift)?;
+        }
+        Ok((mag, bit))
+    }
+
+    // m * 2^b * 10^k as described at the top of this file. Exact values are returned as is, anything else is correctly rounded to cutoff.
+    pub(crate) fn from_magnitude_pow10(neg: bool, mag: Vec<Digit>, bit: isize, pow10: isize, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        BigFixed::from_magnitude_pow(neg, mag, bit, 10, pow10, cutoff)
+    }
+
+    // m * 2^b * radix^k, the same as from_magnitude_pow10 with radix = 2^t * o in place of 10 = 2 * 5
+    pub(crate) fn from_magnitude_pow(neg: bool, mut mag: Vec<Digit>, bit: isize, radix: u32, pow: isize, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        let twos = radix.trailing_zeros() as isize;
+        let odd = mag_from_u128((radix >> twos) as u128);
+        let mut returner;
+        if mag.is_empty() {
+            return Ok(BigFixed::ZERO);
+        } else if pow >= 0 {
+            mul_pow(&mut mag, &odd, Index::uncastsize(pow)?);
+            let low_bit = bit.checked_add(twos.checked_mul(pow).ok_or(MultiplicationOverflow)?).ok_or(AdditionOverflow)?;
+            returner = BigFixed::construct(0, mag, Index::Bit(low_bit))?;
+        } else {
+            let j = Index::uncastsize(-pow)?;
+            let low_bit = bit.checked_add(twos.checked_mul(pow).ok_or(MultiplicationOverflow)?).ok_or(AdditionOverflow)?;
+            let mut exact = mag.clone();
+            if !div_pow(&mut exact, &odd, j) {
+                returner = BigFixed::construct(0, exact, Index::Bit(low_bit))?;
+            } else if cutoff.fixed.is_none() && cutoff.floating.is_none() {
+                return Err(BigFixedParseError(ParseError::NonTerminating));
+            } else {
+                let odd_bits = Index::castsize(j)?.checked_mul(Index::castsize(bit_len(&odd))?).ok_or(MultiplicationOverflow)?;
+                return BigFixed::round_quotient(neg, &mag, low_bit, odd_bits, |q| div_pow(q, &odd, j), cutoff);
+            }
+        }
+        if neg {
+            returner.negate()?;
+        }
+        Ok(returner)
+    }
+
+    // mag * 2^low_bit / d correctly rounded to cutoff by the sticky bit loop described at the top of this file, for a divisor d of at most d_bits
+    // bits which does not divide the numerator. divide replaces its argument by the floor of it over d and reports whether there was a remainder.
+    // The cutoff must bound the precision since the quotient never terminates.
+    pub(crate) fn round_quotient<F: Fn(&mut Vec<Digit>) -> bool>(
+        neg: bool, mag: &[Digit], low_bit: isize, d_bits: isize, divide: F, cutoff: Cutoff
+    ) -> Result<BigFixed, BigFixedError> {
+        // a first guess at s which is corrected below if it was not enough: the value is at least about 2^(bit_len + low_bit - d_bits)
+        let scale = d_bits - Index::castsize(bit_len(mag))? - low_bit;
+        let mut s = Index::castsize(2*DIGITBITS)? + max(scale, 0);
+        if let Some(floating) = cutoff.floating {
+            s += max(floating.bit_value()?, 0);
+        }
+        if let Some(fixed) = cutoff.fixed {
+            s = max(s, 2 - fixed.bit_value()?);
+        }
+        loop {
+            // q = floor(mag * 2^(low_bit + s) / d)
+            let mut q = mag.to_vec();
+            let shift = low_bit + s;
+            let mut sticky = false;
+            if shift >= 0 {
+                shl_bits(&mut q, Index::uncastsize(shift)?);
+            } else {
+                sticky |= shr_bits(&mut q, Index::uncastsize(-shift)?);
+            }
+            sticky |= divide(&mut q);
+            let mut returner = BigFixed::construct(0, q, Index::Bit(-s))?;
+            let nonzero = !returner.is_zero();
+            if sticky {
+                returner.add_digit(1, Index::Bit(-s - 1))?;
+                returner.format()?;
+            }
+            if neg {
+                returner.negate()?;
+            }
+            let cutoff_bit = returner.cutoff_index(cutoff)?.bit_value()?;
+            if nonzero && cutoff_bit > -s {
+                returner.cutoff(cutoff)?;
+                return Ok(returner);
+            }
+            s = if nonzero {max(s + 1, 1 - cutoff_bit)} else {2*s};
+        }
+    }
+}
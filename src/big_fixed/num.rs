@@ -0,0 +1,212 @@
+/*
+    num-traits support behind the num-traits feature, for plugging BigFixed into generic numeric code. BigFixed gets Zero, One, FromPrimitive,
+    ToPrimitive, Pow, CheckedAdd, CheckedSub and CheckedMul. Num (and so Signed), CheckedDiv and CheckedRem all need / and %, which BigFixed does
+    not have since exact division would panic on something as ordinary as 1 / 3; the inherent checked_div and checked_rem in ratio.rs stand in for
+    the last two outside generic code. An unbounded type has no Bounded. CutoffBoundBigFixed
+    divides under its scheme but every value carries that scheme, so it only gets the traits which do not make a value out of nothing: ToPrimitive,
+    Pow and the four checked operations.
+
+    ToPrimitive conversions to integers truncate towards zero, as the primitive float impls do, so -2.75 gives -2 and -0.5 gives 0 even as an
+    unsigned integer. They give None out of range. Checked operations give None on errors and on division by zero. Pow multiplies by repeated
+    squaring, exactly for BigFixed and cut off after every multiplication for CutoffBoundBigFixed.
+*/
+
+use crate::{BigFixed, BigFixedError, CutoffBoundBigFixed, Rounding};
+
+use num_traits::{Zero, One, FromPrimitive, ToPrimitive, Pow, CheckedAdd, CheckedSub, CheckedMul, CheckedDiv};
+
+impl Zero for BigFixed {
+    fn zero() -> BigFixed {
+        BigFixed::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        BigFixed::is_zero(self)
+    }
+}
+
+impl One for BigFixed {
+    fn one() -> BigFixed {
+        BigFixed::from(1)
+    }
+}
+
+impl FromPrimitive for BigFixed {
+    fn from_i64(n: i64) -> Option<BigFixed> {
+        Some(BigFixed::from(n))
+    }
+
+    fn from_u64(n: u64) -> Option<BigFixed> {
+        Some(BigFixed::from(n))
+    }
+
+    fn from_i128(n: i128) -> Option<BigFixed> {
+        Some(BigFixed::from(n))
+    }
+
+    fn from_u128(n: u128) -> Option<BigFixed> {
+        Some(BigFixed::from(n))
+    }
+
+    // infinities and NaN have no BigFixed
+    fn from_f32(n: f32) -> Option<BigFixed> {
+        BigFixed::try_from(n).ok()
+    }
+
+    fn from_f64(n: f64) -> Option<BigFixed> {
+        BigFixed::try_from(n).ok()
+    }
+}
+
+// truncates towards zero
+impl ToPrimitive for BigFixed {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_i64_rounded(Rounding::TowardsZero).ok()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.to_u64_rounded(Rounding::TowardsZero).ok()
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        self.to_i128_rounded(Rounding::TowardsZero).ok()
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        self.to_u128_rounded(Rounding::TowardsZero).ok()
+    }
+
+    fn to_f32(&self) -> Option<f32> {
+        Some(f32::from(self))
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(f64::from(self))
+    }
+}
+
+impl ToPrimitive for CutoffBoundBigFixed {
+    fn to_i64(&self) -> Option<i64> {
+        self.value.to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.value.to_u64()
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        self.value.to_i128()
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        self.value.to_u128()
+    }
+
+    fn to_f32(&self) -> Option<f32> {
+        self.value.to_f32()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.value.to_f64()
+    }
+}
+
+// base^exp by repeated squaring with the given multiplication
+fn power<T: Clone>(base: &T, one: T, mut exp: usize, mul_assign: fn(&mut T, &T) -> Result<(), BigFixedError>) -> Result<T, BigFixedError> {
+    let mut returner = one;
+    let mut square = base.clone();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            mul_assign(&mut returner, &square)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            let factor = square.clone();
+            mul_assign(&mut square, &factor)?;
+        }
+    }
+    Ok(returner)
+}
+
+macro_rules! pow {
+    ($($exp: ty),*) => {
+        $(
+            impl Pow<$exp> for &BigFixed {
+                type Output = BigFixed;
+                fn pow(self, exp: $exp) -> BigFixed {
+                    power(self, BigFixed::from(1), exp as usize, BigFixed::mul_assign).unwrap()
+                }
+            }
+
+            impl Pow<$exp> for BigFixed {
+                type Output = BigFixed;
+                fn pow(self, exp: $exp) -> BigFixed {
+                    (&self).pow(exp)
+                }
+            }
+
+            impl Pow<$exp> for &CutoffBoundBigFixed {
+                type Output = CutoffBoundBigFixed;
+                fn pow(self, exp: $exp) -> CutoffBoundBigFixed {
+                    power(self, self.scheme.claim(BigFixed::from(1)).unwrap(), exp as usize, CutoffBoundBigFixed::mul_assign_s).unwrap()
+                }
+            }
+
+            impl Pow<$exp> for CutoffBoundBigFixed {
+                type Output = CutoffBoundBigFixed;
+                fn pow(self, exp: $exp) -> CutoffBoundBigFixed {
+                    (&self).pow(exp)
+                }
+            }
+        )*
+    };
+}
+
+pow!(u8, u16, u32, usize);
+
+impl CheckedAdd for BigFixed {
+    fn checked_add(&self, v: &BigFixed) -> Option<BigFixed> {
+        BigFixed::add(self, v).ok()
+    }
+}
+
+impl CheckedSub for BigFixed {
+    fn checked_sub(&self, v: &BigFixed) -> Option<BigFixed> {
+        BigFixed::sub(self, v).ok()
+    }
+}
+
+impl CheckedMul for BigFixed {
+    fn checked_mul(&self, v: &BigFixed) -> Option<BigFixed> {
+        BigFixed::mul(self, v).ok()
+    }
+}
+
+impl CheckedAdd for CutoffBoundBigFixed {
+    fn checked_add(&self, v: &CutoffBoundBigFixed) -> Option<CutoffBoundBigFixed> {
+        self.add_s(v).ok()
+    }
+}
+
+impl CheckedSub for CutoffBoundBigFixed {
+    fn checked_sub(&self, v: &CutoffBoundBigFixed) -> Option<CutoffBoundBigFixed> {
+        self.sub_s(v).ok()
+    }
+}
+
+impl CheckedMul for CutoffBoundBigFixed {
+    fn checked_mul(&self, v: &CutoffBoundBigFixed) -> Option<CutoffBoundBigFixed> {
+        self.mul_s(v).ok()
+    }
+}
+
+impl CheckedDiv for CutoffBoundBigFixed {
+    fn checked_div(&self, v: &CutoffBoundBigFixed) -> Option<CutoffBoundBigFixed> {
+        if v.value.is_zero() {
+            return None;
+        }
+        let mut returner = self.clone();
+        returner.div_assign(&v.value).ok()?;
+        Some(returner)
+    }
+}
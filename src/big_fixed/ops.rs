@@ -1,6 +1,6 @@
 use crate::{digit::*, Index, Cutoff, BigFixed, BigFixedError, macros::*};
 
-use std::{
+use core::{
     ops::{
         Add, AddAssign,
         BitAnd, BitAndAssign,
@@ -12,9 +12,12 @@ use std::{
         Shr, ShrAssign,
         Sub, SubAssign
     },
-    cmp::{max, min, Ordering}
+    cmp::{max, min, Ordering},
+    hash::{Hash, Hasher}
 };
 
+use alloc::{vec::Vec, vec};
+
 impl BigFixed {
     // Add digit into position and handle carries
     pub fn add_digit(&mut self, d: Digit, position: Index) -> Result<(), BigFixedError> {
@@ -109,17 +112,31 @@ impl BigFixed {
     }
 
     pub fn add_assign(&mut self, other: &BigFixed) -> Result<(), BigFixedError> {
+        self.align_with(other)?;
+        let offset = (other.position - self.position)?.unsigned_value();
+        let mut carry: DoubleDigit = 0;
+        for (i, x) in self.body[offset..].iter_mut().enumerate() {
+            let res = (*x as DoubleDigit) + (*other.body.get(i).unwrap_or(&other.head) as DoubleDigit) + carry;
+            *x = res as Digit;
+            carry = res >> DIGITBITS;
+        }
+        self.finish_aligned()
+    }
+
+    // Extend self in place (no allocation if the capacity is there) to cover other and one more position for overflow, so addition and
+    // subtraction become a single carry or borrow pass over the body of self from the position of other up
+    fn align_with(&mut self, other: &BigFixed) -> Result<(), BigFixedError> {
+        assert!(other.properly_positioned());
         self.fix_position()?;
         let position = min(self.position, other.position);
-        // one more for overflow
         let high = (max(self.body_high()?, other.body_high()?) + Index::Position(1))?;
         self.ensure_valid_range(position, high)?;
-        let other_low = other.position.cast_to_position();
-        for i in other_low.value()..high.value() {
-            let p = Index::Position(i);
-            self.add_digit_drop_overflow(other[p], p)?;
-        }
-        self.head = if self[(high - Index::Position(1))?] >= GREATESTBIT {
+        Ok(())
+    }
+
+    // after a pass over an aligned body the top digit only carries the sign
+    fn finish_aligned(&mut self) -> Result<(), BigFixedError> {
+        self.head = if self.body.last().is_some_and(|&x| x >= GREATESTBIT) {
             ALLONES
         } else {
             0
@@ -222,7 +239,7 @@ impl BigFixed {
             self.add_digit_drop_overflow(summ_res, Index::Position(Index::castsize(i + 1)?))?;
             self.add_digit_drop_overflow(totall_carry, Index::Position(Index::castsize(i + 2)?))?;
         }
-        self.head = self.head ^ other.head;
+        self.head ^= other.head;
         self.position = low;
         self.format()
     }
@@ -241,10 +258,16 @@ impl BigFixed {
 
     // could save a few runtime steps by making a subtract_digit method but this is easier to build
     pub fn sub_assign(&mut self, other: &BigFixed) -> Result<(), BigFixedError> {
-        self.negate()?;
-        self.add_assign(other)?;
-        self.negate()?;
-        Ok(())
+        self.align_with(other)?;
+        let offset = (other.position - self.position)?.unsigned_value();
+        let mut borrow = false;
+        for (i, x) in self.body[offset..].iter_mut().enumerate() {
+            let (diff, o1) = x.overflowing_sub(*other.body.get(i).unwrap_or(&other.head));
+            let (diff, o2) = diff.overflowing_sub(borrow as Digit);
+            *x = diff;
+            borrow = o1 || o2;
+        }
+        self.finish_aligned()
     }
 }
 
@@ -274,7 +297,7 @@ impl BigFixed {
         Ok (
             (
                 ((self[self.body_high()?] as DoubleDigit) << DIGITBITS)
-                | ((self[(self.body_high()? - Index::Position(1))?] as DoubleDigit))
+                | (self[(self.body_high()? - Index::Position(1))?] as DoubleDigit)
             ) as SignedDoubleDigit
         )
     }
@@ -284,7 +307,7 @@ impl BigFixed {
         // Go until num (the remainder) is small enough so that num / denom has 0s in all positions >= -to, i.e. num < denom / base^to.
         // sign stuff
         let mut quotient = BigFixed::from(0);
-        if &*num < &quotient {
+        if *num < quotient {
             num.negate()?;
             quotient = BigFixed::combined_div(num, denom, end)?;
             num.negate()?;
@@ -298,7 +321,7 @@ impl BigFixed {
             return Ok(quotient);
         }
         assert!(!denom.is_zero(), "divide by zero");
-        assert!(&*num >= &quotient && denom >= &quotient, "sign issue");
+        assert!(*num >= quotient && denom >= &quotient, "sign issue");
 
         // starting the actual division
         let denom_tail_len = denom.body.len() - 1;
@@ -380,32 +403,133 @@ impl BigFixed {
 
     pub fn to_digits_10(&self) -> Result<(Vec<i32>, isize), BigFixedError> {
         let (big_digits, point) = self.to_digits(&BigFixed::from(10))?;
-        let digits: Vec<i32> = big_digits.iter().map(|x| i32::from(x)).collect();
+        let digits: Vec<i32> = big_digits.iter().map(|x| x.to_i32_saturating()).collect();
         Ok((digits, point))
     }
 }
 
 // -- end division section
 
-impl PartialOrd for BigFixed {
-    fn partial_cmp(&self, other: &BigFixed) -> Option<Ordering> {
-        let mut step_result = self.head.cmp(&other.head);
-        match step_result {
+// Equality, comparison and hashing go by numeric value whatever the formatting, so BigFixed works as a BTreeMap or HashMap key. min, max and clamp
+// come with Ord. structurally_eq compares representations.
+
+impl PartialEq for BigFixed {
+    fn eq(&self, other: &BigFixed) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigFixed {}
+
+// The digits of a BigFixed read in place at absolute positions. Positions are counted in i128 and a Bit position converted to bits cannot
+// overflow it, so reading never fails and works the same for Position and Bit formatted values.
+struct DigitView<'a> {
+    value: &'a BigFixed,
+    // the bit at which body[0] starts
+    low_bit: i128,
+    // the digits at positions in bottom..=top come from the body; above is the head and below is zero
+    bottom: i128,
+    top: i128,
+    head: Digit
+}
+
+impl<'a> DigitView<'a> {
+    fn new(value: &'a BigFixed) -> DigitView<'a> {
+        let bits = DIGITBITS as i128;
+        let low_bit = match value.position {
+            Index::Position(p) => p as i128 * bits,
+            Index::Bit(b) => b as i128
+        };
+        let bottom = low_bit.div_euclid(bits);
+        DigitView {
+            value,
+            low_bit,
+            bottom,
+            top: if value.body.is_empty() {bottom - 1} else {(low_bit + bits * value.body.len() as i128 - 1).div_euclid(bits)},
+            head: if value.head == 0 {0} else {ALLONES}
+        }
+    }
+
+    fn limb(&self, i: i128) -> Digit {
+        if i < 0 {
+            0
+        } else if i >= self.value.body.len() as i128 {
+            self.head
+        } else {
+            self.value.body[i as usize]
+        }
+    }
+
+    fn digit(&self, position: i128) -> Digit {
+        if position > self.top {
+            return self.head;
+        }
+        if position < self.bottom {
+            return 0;
+        }
+        let offset = position * DIGITBITS as i128 - self.low_bit;
+        let i = offset.div_euclid(DIGITBITS as i128);
+        let shift = offset.rem_euclid(DIGITBITS as i128);
+        let pair = ((self.limb(i + 1) as DoubleDigit) << DIGITBITS) | (self.limb(i) as DoubleDigit);
+        (pair >> shift) as Digit
+    }
+
+    // the next position below position which could hold a different digit from the one at position
+    fn next_below(&self, position: i128) -> i128 {
+        if position > self.top {
+            self.top
+        } else if position > self.bottom {
+            position - 1
+        } else {
+            i128::MIN
+        }
+    }
+}
+
+impl Ord for BigFixed {
+    fn cmp(&self, other: &BigFixed) -> Ordering {
+        let (a, b) = (DigitView::new(self), DigitView::new(other));
+        // a negative head sorts first
+        match b.head.cmp(&a.head) {
             Ordering::Equal => {
-                for i in (
-                    min(self.position.cast_to_position(), other.position.cast_to_position()).value()..
-                    (max(self.body_high().ok()?.cast_to_position(), other.body_high().ok()?.cast_to_position())).value()
-                ).rev() {
-                    step_result = self[i].cmp(&other[i]);
-                    match step_result {
-                        Ordering::Equal => continue,
-                        x => return Some(x)
+                // both are constant between the positions next_below gives, so those stretches are compared once and skipped
+                let end = min(a.bottom, b.bottom);
+                let mut position = max(a.top, b.top);
+                while position >= end {
+                    match a.digit(position).cmp(&b.digit(position)) {
+                        Ordering::Equal => position = max(a.next_below(position), b.next_below(position)),
+                        x => return x
                     }
                 }
-                Some(Ordering::Equal)
+                Ordering::Equal
             },
-            Ordering::Less => Some(Ordering::Greater),
-            Ordering::Greater => Some(Ordering::Less)
+            x => x
+        }
+    }
+}
+
+impl PartialOrd for BigFixed {
+    fn partial_cmp(&self, other: &BigFixed) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Hashes what determines the value: the head, the position of the lowest nonzero digit and the digits from there up to the last one which is not
+// the head. They are read in place through a DigitView, so the body padding, the position and its format do not matter and nothing can fail.
+impl Hash for BigFixed {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let view = DigitView::new(self);
+        view.head.hash(state);
+        // the digit just above the body is the head, so only zero has no nonzero digit
+        let low = match (view.bottom..=view.top + 1).find(|&p| view.digit(p) != 0) {
+            Some(low) => low,
+            None => return
+        };
+        let high = (low..=view.top).rev().find(|&p| view.digit(p) != view.head).unwrap_or(low - 1);
+        low.hash(state);
+        high.hash(state);
+        for p in low..=high {
+            view.digit(p).hash(state);
         }
     }
 }
@@ -1,11 +1,21 @@
 /*
-    Cutoff operations for BigFixed. Essentially the process is to do the pure operation then cut off the result. Later on we can implement an optimization
-    to avoid computing data which is just going to be cut off but for now we do the easy way
+    Cutoff operations for BigFixed. Most of them do the pure operation then cut off the result. Addition, subtraction and multiplication avoid computing
+    limbs which are only going to be cut off. The cutoff bit of the result is estimated from the operands and everything more than GUARD limbs below it
+    is left out: operand limbs for addition and subtraction, product columns for multiplication (a short product). What is left out is bounded, so the
+    exact result is known to lie in an interval [low, high]. If low and high fall strictly inside the same cell of width 2^(cutoff bit - 1), and that
+    cell is not next to zero when the cutoff floats, every rounding mode treats the whole interval the same way and cutting off low gives exactly what
+    the full operation would have. Otherwise (the estimate was off, or the result sits on or very near a rounding boundary) the correction step is to
+    fall back to the full operation, so results are identical to the pure operation followed by a cutoff for every Rounding.
+
+    mul_add_c is a fused multiply-add: the product is not rounded on its own, so self * a + b is cut off exactly once.
+
+    Comparisons under a cutoff do not build the cut off values at all. A CutoffView reads them limb by limb from the original, working out the rounding
+    increment and how far it carries up front, so comparing CutoffBoundBigFixeds does not allocate.
 */
 
-use crate::{digit::*, Index, Cutoff, BigFixed, BigFixedError, macros::*};
+use crate::{digit::*, Index, Cutoff, Rounding, BigFixed, BigFixedError, macros::*, big_fixed::magnitude::mul_mag_high};
 
-use std::{
+use core::{
     ops::{
         Add, AddAssign,
         BitAnd, BitAndAssign,
@@ -16,7 +26,7 @@ use std::{
         Shr, ShrAssign,
         Sub, SubAssign
     },
-    cmp::{Ordering}
+    cmp::{max, min, Ordering}
 };
 
 impl BigFixed {
@@ -32,29 +42,33 @@ impl BigFixed {
     }
 
     pub fn is_zero_c(&self, cutoff: Cutoff) -> Result<bool, BigFixedError> {
-        let mut avoidable_clone = self.clone();
-        avoidable_clone.cutoff(cutoff)?;
-        Ok(avoidable_clone.is_zero())
+        let value = self.positioned()?;
+        CutoffView::new(&value, cutoff)?.is_zero()
     }
 
     pub fn full_eq_c(&self, other: &BigFixed, cutoff: Cutoff) -> Result<bool, BigFixedError> {
-        let mut avoidable_clone_1 = self.clone();
-        let mut avoidable_clone_2 = other.clone();
-        avoidable_clone_1.cutoff(cutoff)?;
-        avoidable_clone_2.cutoff(cutoff)?;
-        avoidable_clone_1.full_eq(&avoidable_clone_2)
+        let (value, other_value) = (self.positioned()?, other.positioned()?);
+        let (a, b) = (CutoffView::new(&value, cutoff)?, CutoffView::new(&other_value, cutoff)?);
+        if a.head != b.head {
+            return Ok(false);
+        }
+        for p in min(a.position, b.position)..max(a.high()?, b.high()?) {
+            if a.digit(p) != b.digit(p) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
     }
 
     pub fn cutoff_index_c(&self, cutoff_c: Cutoff, cutoff: Cutoff) -> Result<Index, BigFixedError> {
-        let mut avoidable_clone = self.clone();
-        avoidable_clone.cutoff(cutoff)?;
-        avoidable_clone.cutoff_index(cutoff_c)
+        let value = self.positioned()?;
+        let view = CutoffView::new(&value, cutoff)?;
+        BigFixed::cutoff_index_for(view.formatted_position()?, || view.greatest_bit_position(), cutoff_c)
     }
 
     pub fn greatest_bit_position_c(&self, cutoff: Cutoff) -> Result<Index, BigFixedError> {
-        let mut avoidable_clone = self.clone();
-        avoidable_clone.cutoff(cutoff)?;
-        avoidable_clone.greatest_bit_position()
+        let value = self.positioned()?;
+        CutoffView::new(&value, cutoff)?.greatest_bit_position()
     }
 
     // Add digit into position and handle carries
@@ -76,17 +90,231 @@ impl BigFixed {
     }
 }
 
+// limbs kept below the one holding the bit under the estimated cutoff
+const GUARD: isize = 2;
+
+// the cutoff bit of a result whose greatest bit is about greatest, None if cutoff keeps everything
+fn estimated_cutoff_bit(cutoff: Cutoff, greatest: isize) -> Result<Option<isize>, BigFixedError> {
+    let fixed = match cutoff.fixed {
+        Some(fixed) => Some(fixed.bit_value()?),
+        None => None
+    };
+    let floating = match cutoff.floating {
+        Some(floating) => Some(greatest - max(floating.bit_value()?, 0)),
+        None => None
+    };
+    Ok(match (fixed, floating) {
+        (Some(fixed), Some(floating)) => Some(min(fixed, floating)),
+        (fixed, None) => fixed,
+        (None, floating) => floating
+    })
+}
+
+// the position below which limbs are left out for an estimated cutoff bit
+fn truncation_position(bit: isize) -> Result<isize, BigFixedError> {
+    Ok((bit - 1).div_euclid(Index::castsize(DIGITBITS)?) - GUARD)
+}
+
+impl BigFixed {
+    // self with the limbs below position dropped, which is self floored to position, and whether anything nonzero was dropped
+    fn truncated(&self, position: isize) -> Result<(BigFixed, bool), BigFixedError> {
+        let low = self.position.value();
+        if low >= position {
+            return Ok((self.clone(), false));
+        }
+        let drop = min(Index::uncastsize(position - low)?, self.body.len());
+        let dropped = self.body[..drop].iter().any(|&x| x != 0);
+        Ok((BigFixed::construct(self.head, &self.body[drop..], Index::Position(position))?, dropped))
+    }
+
+    // The exact result lies in [low, high]. Cut it off if that can be done without knowing it exactly, as described at the top of this file.
+    fn settle(mut low: BigFixed, high: &BigFixed, cutoff: Cutoff) -> Result<Option<BigFixed>, BigFixedError> {
+        let index = low.cutoff_index(cutoff)?.bit_value()?;
+        if index != high.cutoff_index(cutoff)?.bit_value()? {
+            return Ok(None);
+        }
+        let cell = Cutoff {
+            fixed: Some(Index::Bit(index - 1)),
+            floating: None,
+            round: Rounding::Floor
+        };
+        let mut low_cell = low.clone();
+        low_cell.cutoff(cell)?;
+        let mut high_cell = high.clone();
+        high_cell.cutoff(cell)?;
+        if !low_cell.full_eq(&high_cell)? || low_cell.full_eq(&low)? {
+            return Ok(None);
+        }
+        // the greatest bit, and so a floating cutoff index, only stays put across cells which do not touch zero
+        if cutoff.floating.is_some() {
+            if low_cell.is_zero() {
+                return Ok(None);
+            }
+            low_cell.add_digit(1, Index::Bit(index - 1))?;
+            if low_cell.is_zero() {
+                return Ok(None);
+            }
+        }
+        low.cutoff(cutoff)?;
+        Ok(Some(low))
+    }
+
+    // self + other or self - other cut off, skipping operand limbs far below the cutoff. None if the full operation is needed.
+    fn short_add(&self, other: &BigFixed, subtract: bool, cutoff: Cutoff) -> Result<Option<BigFixed>, BigFixedError> {
+        if !self.properly_positioned() || !other.properly_positioned() {
+            return Ok(None);
+        }
+        let greatest = max(self.greatest_bit_position()?.bit_value()?, other.greatest_bit_position()?.bit_value()?);
+        let position = match estimated_cutoff_bit(cutoff, greatest)? {
+            Some(bit) => truncation_position(bit)?,
+            None => return Ok(None)
+        };
+        if self.position.value() >= position && other.position.value() >= position {
+            return Ok(None);
+        }
+        let (mut low, self_dropped) = self.truncated(position)?;
+        let (other_high, other_dropped) = other.truncated(position)?;
+        if subtract {
+            low.sub_assign(&other_high)?;
+        } else {
+            low.add_assign(&other_high)?;
+        }
+        // each dropped part was in [0, 2^(DIGITBITS * position))
+        let unit = BigFixed::from(1).shift(Index::Position(position))?;
+        let mut high = low.clone();
+        if self_dropped {
+            high.add_assign(&unit)?;
+        }
+        if other_dropped {
+            if subtract {
+                low.sub_assign(&unit)?;
+            } else {
+                high.add_assign(&unit)?;
+            }
+        }
+        BigFixed::settle(low, &high, cutoff)
+    }
+
+    // self * other cut off by a short product which leaves out the columns far below the cutoff. None if the full product is needed.
+    fn short_mul(&self, other: &BigFixed, cutoff: Cutoff) -> Result<Option<BigFixed>, BigFixedError> {
+        if !self.properly_positioned() || !other.properly_positioned() || self.is_zero() || other.is_zero() {
+            return Ok(None);
+        }
+        let a = self.abs()?;
+        let b = other.abs()?;
+        let greatest = a.greatest_bit_position()?.bit_value()? + b.greatest_bit_position()?.bit_value()?;
+        let low = a.position.value() + b.position.value();
+        let skip = match estimated_cutoff_bit(cutoff, greatest)? {
+            Some(bit) => truncation_position(bit)? - low,
+            None => return Ok(None)
+        };
+        if skip <= 0 {
+            return Ok(None);
+        }
+        let mut approximation = BigFixed::construct(0, mul_mag_high(&a.body, &b.body, Index::uncastsize(skip)?), Index::Position(low + skip))?;
+        let mut bound = approximation.clone();
+        bound.add_assign(&BigFixed::from(min(a.body.len(), b.body.len())).shift(Index::Position(low + skip + 1))?)?;
+        if self.is_neg() != other.is_neg() {
+            approximation.negate()?;
+            bound.negate()?;
+            BigFixed::settle(bound, &approximation, cutoff)
+        } else {
+            BigFixed::settle(approximation, &bound, cutoff)
+        }
+    }
+
+    pub fn add_assign_c(&mut self, other: &BigFixed, cutoff: Cutoff) -> Result<(), BigFixedError> {
+        match self.short_add(other, false, cutoff)? {
+            Some(sum) => *self = sum,
+            None => {
+                self.add_assign(other)?;
+                self.cutoff(cutoff)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_c(&self, other: &BigFixed, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        match self.short_add(other, false, cutoff)? {
+            Some(sum) => Ok(sum),
+            None => {
+                let mut res = self.add(other)?;
+                res.cutoff(cutoff)?;
+                Ok(res)
+            }
+        }
+    }
+
+    pub fn sub_assign_c(&mut self, other: &BigFixed, cutoff: Cutoff) -> Result<(), BigFixedError> {
+        match self.short_add(other, true, cutoff)? {
+            Some(difference) => *self = difference,
+            None => {
+                self.sub_assign(other)?;
+                self.cutoff(cutoff)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn sub_c(&self, other: &BigFixed, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        match self.short_add(other, true, cutoff)? {
+            Some(difference) => Ok(difference),
+            None => {
+                let mut res = self.sub(other)?;
+                res.cutoff(cutoff)?;
+                Ok(res)
+            }
+        }
+    }
+
+    pub fn mul_assign_c(&mut self, other: &BigFixed, cutoff: Cutoff) -> Result<(), BigFixedError> {
+        match self.short_mul(other, cutoff)? {
+            Some(product) => *self = product,
+            None => {
+                self.mul_assign(other)?;
+                self.cutoff(cutoff)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn mul_c(&self, other: &BigFixed, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        match self.short_mul(other, cutoff)? {
+            Some(product) => Ok(product),
+            None => {
+                let mut res = self.mul(other)?;
+                res.cutoff(cutoff)?;
+                Ok(res)
+            }
+        }
+    }
+
+    // self * a + b with a single rounding, like a floating point fma. The product is kept exact so it is cut off once with the sum.
+    pub fn mul_add_assign_c(&mut self, a: &BigFixed, b: &BigFixed, cutoff: Cutoff) -> Result<(), BigFixedError> {
+        self.mul_assign(a)?;
+        self.add_assign(b)?;
+        self.cutoff(cutoff)
+    }
+
+    pub fn mul_add_c(&self, a: &BigFixed, b: &BigFixed, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        let mut res = self.mul(a)?;
+        res.add_assign(b)?;
+        res.cutoff(cutoff)?;
+        Ok(res)
+    }
+}
+
 /*
 op_assign_to_op!(op, op_fn_name, op_assign, op_assign_fn_name, op_assign_c_fn_name, self_type, other_type, cutoff_type, result_type, error_type)
 */
-cutoff_op!(Add, add, add_c, AddAssign, add_assign, add_assign_c, BigFixed, BigFixed, Cutoff, cutoff, BigFixed, BigFixedError);
+cutoff_op!(impls; Add, add, add_c, AddAssign, add_assign, add_assign_c, BigFixed, BigFixed, Cutoff, BigFixed);
 cutoff_op!(BitAnd, bitand, bitand_c, BitAndAssign, bitand_assign, bitand_assign_c, BigFixed, BigFixed, Cutoff, cutoff, BigFixed, BigFixedError);
 cutoff_op!(BitOr, bitor, bitor_c, BitOrAssign, bitor_assign, bitor_assign_c, BigFixed, BigFixed, Cutoff, cutoff, BigFixed, BigFixedError);
 cutoff_op!(BitXor, bitxor, bitxor_c, BitXorAssign, bitxor_assign, bitxor_assign_c, BigFixed, BigFixed, Cutoff, cutoff, BigFixed, BigFixedError);
-cutoff_op!(Mul, mul, mul_c, MulAssign, mul_assign, mul_assign_c, BigFixed, BigFixed, Cutoff, cutoff, BigFixed, BigFixedError);
+cutoff_op!(impls; Mul, mul, mul_c, MulAssign, mul_assign, mul_assign_c, BigFixed, BigFixed, Cutoff, BigFixed);
 cutoff_op!(Shl, shl, shl_c, ShlAssign, shl_assign, shl_assign_c, BigFixed, usize, Cutoff, cutoff, BigFixed, BigFixedError);
 cutoff_op!(Shr, shr, shr_c, ShrAssign, shr_assign, shr_assign_c, BigFixed, usize, Cutoff, cutoff, BigFixed, BigFixedError);
-cutoff_op!(Sub, sub, sub_c, SubAssign, sub_assign, sub_assign_c, BigFixed, BigFixed, Cutoff, cutoff, BigFixed, BigFixedError);
+cutoff_op!(impls; Sub, sub, sub_c, SubAssign, sub_assign, sub_assign_c, BigFixed, BigFixed, Cutoff, BigFixed);
 
 cutoff_op!(Neg, neg, BigFixed, negate, negate_c, Cutoff, cutoff, BigFixedError);
 
@@ -118,18 +346,133 @@ cutoff_op!(Neg, neg, BigFixed, negate, negate_c, Cutoff, cutoff, BigFixedError);
 
     pub fn to_digits_10_c(&self, max_len: usize) -> Result<(Vec<i32>, isize), BigFixedError> {
         let (big_digits, point) = self.to_digits_c(&BigFixed::from(10), max_len)?;
-        let digits: Vec<i32> = big_digits.iter().map(|x| i32::from(x)).collect();
+        let digits: Vec<i32> = big_digits.iter().map(|x| x.to_i32_saturating()).collect();
         Ok((digits, point))
     }
 }*/
 
 impl BigFixed {
     pub fn partial_cmp_c(&self, other: &BigFixed, cutoff: Cutoff) -> Option<Ordering> {
-        let mut avoidable_clone_1 = self.clone();
-        let mut avoidable_clone_2 = other.clone();
-        avoidable_clone_1.cutoff(cutoff).ok()?;
-        avoidable_clone_2.cutoff(cutoff).ok()?;
-        avoidable_clone_1.partial_cmp(&avoidable_clone_2)
+        let (value, other_value) = (self.positioned().ok()?, other.positioned().ok()?);
+        let (a, b) = (CutoffView::new(&value, cutoff).ok()?, CutoffView::new(&other_value, cutoff).ok()?);
+        // a negative head sorts first
+        match b.head.cmp(&a.head) {
+            Ordering::Equal => {
+                for p in (min(a.position, b.position)..max(a.high().ok()?, b.high().ok()?)).rev() {
+                    match a.digit(p).cmp(&b.digit(p)) {
+                        Ordering::Equal => continue,
+                        x => return Some(x)
+                    }
+                }
+                Some(Ordering::Equal)
+            },
+            x => Some(x)
+        }
     }
 }
 
+// The value a BigFixed would have after a cutoff, read limb by limb instead of built. Those are the limbs from the cutoff position up with the bits of
+// the lowest one below the cutoff bit masked off, plus one at the cutoff bit when the rounding goes up. The increment carries through the limbs from
+// the cutoff position up to carry, which wrap around to zero. carry is None when it runs through a negative head, leaving zero.
+struct CutoffView<'a> {
+    value: &'a BigFixed,
+    position: isize,
+    shift: usize,
+    increment: bool,
+    carry: Option<isize>,
+    head: Digit
+}
+
+impl<'a> CutoffView<'a> {
+    // value must be properly positioned
+    fn new(value: &'a BigFixed, cutoff: Cutoff) -> Result<CutoffView<'a>, BigFixedError> {
+        let index = value.cutoff_index(cutoff)?;
+        let as_bit = index.cast_to_bit()?;
+        let position = index.cast_to_position().value();
+        let shift = Index::uncastsize((as_bit - Index::Position(position))?.value())?;
+        let round = match cutoff.round {
+            Rounding::TowardsZero => if value.is_neg() {Rounding::Ceiling} else {Rounding::Floor},
+            Rounding::AwayFromZero => if value.is_neg() {Rounding::Floor} else {Rounding::Ceiling},
+            round => round
+        };
+        let increment = match round {
+            Rounding::Floor => false,
+            Rounding::Round => value[(as_bit - Index::Bit(1))?] > 0,
+            // Ceiling, anything nonzero below the cutoff bit
+            _ => value[position] & !(ALLONES << shift) != 0 || (value.position.value()..position).any(|p| value[p] != 0)
+        };
+        let mut carry = None;
+        if increment {
+            let mut p = position;
+            let mut d = (value[p] & (ALLONES << shift)) as DoubleDigit + (1 << shift);
+            let high = value.body_high()?.value();
+            while d > ALLONES as DoubleDigit && !(p >= high && value.is_neg()) {
+                p += 1;
+                d = value[p] as DoubleDigit + 1;
+            }
+            if d <= ALLONES as DoubleDigit {
+                carry = Some(p);
+            }
+        }
+        Ok(CutoffView {
+            value,
+            position,
+            shift,
+            increment,
+            carry,
+            head: if increment && carry.is_none() {0} else {value.head}
+        })
+    }
+
+    fn digit(&self, p: isize) -> Digit {
+        if p < self.position {
+            return 0;
+        }
+        let d = if p == self.position {self.value[p] & (ALLONES << self.shift)} else {self.value[p]};
+        match (self.increment, self.carry) {
+            (false, _) => d,
+            (true, None) => 0,
+            (true, Some(carry)) => match p.cmp(&carry) {
+                Ordering::Less => 0,
+                Ordering::Equal => d + if p == self.position {1 << self.shift} else {1},
+                Ordering::Greater => d
+            }
+        }
+    }
+
+    // every limb from here up is head
+    fn high(&self) -> Result<isize, BigFixedError> {
+        let high = max(self.value.body_high()?.value(), self.position + 1);
+        Ok(match self.carry {
+            Some(carry) => max(high, carry + 1),
+            None => high
+        })
+    }
+
+    fn is_zero(&self) -> Result<bool, BigFixedError> {
+        Ok(self.head == 0 && (self.position..self.high()?).all(|p| self.digit(p) == 0))
+    }
+
+    // the position of the cut off value once formatted, that is its lowest nonzero limb
+    fn formatted_position(&self) -> Result<Index, BigFixedError> {
+        if self.is_zero()? {
+            return Ok(Index::Position(0));
+        }
+        let mut p = self.position;
+        while self.digit(p) == 0 {
+            p += 1;
+        }
+        Ok(Index::Position(p))
+    }
+
+    fn greatest_bit_position(&self) -> Result<Index, BigFixedError> {
+        // below a negative value's lowest nonzero limb there is a zero limb which differs from the head
+        for p in ((self.position - 1)..self.high()?).rev() {
+            let coefficient = self.digit(p) ^ self.head;
+            if coefficient != 0 {
+                return Ok(Index::Bit(Index::Position(p + 1).bit_value()? - Index::castsize(coefficient.leading_zeros() as usize + 1)?));
+            }
+        }
+        Ok(Index::Position(0))
+    }
+}
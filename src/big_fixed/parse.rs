@@ -0,0 +1,155 @@
+/*
+    String parsing. Decimal input is read into an unsigned integer mantissa m and a decimal exponent k so that the value is m * 10^k. Nonnegative k
+    is an integer and always exact. Negative k = -j gives m / 10^j = (m / 5^j) * 2^-j which is exact iff 5^j divides m; otherwise the binary expansion
+    never terminates and the result is rounded to the requested cutoff.
+
+    Hexadecimal, octal and binary literals (prefixed 0x, 0o, 0b) follow the C hex float convention: the optional exponent after p is a power of two.
+    Every digit is a whole number of bits so these are always exact.
+
+    Correct rounding of the decimal case is done by BigFixed::from_magnitude_pow10. A decimal exponent costs about log2(10) bits of memory per unit
+    whatever its sign, so literals whose mantissa and exponent together would need more than MAX_PARSE_BITS bits fail with TooLarge
+    before anything is built.
+*/
+
+use crate::{digit::*, Index, Cutoff, Rounding, BigFixed, BigFixedError, big_fixed::magnitude::*};
+
+use core::str::FromStr;
+
+use alloc::{vec::Vec, vec};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseError {
+    Empty,
+    InvalidDigit,
+    InvalidExponent,
+    // the value has no terminating binary expansion and the cutoff does not restrict precision
+    NonTerminating,
+    // the decimal exponent is too large in magnitude to work with, see MAX_PARSE_BITS
+    TooLarge,
+    // a radix below 2 given to to_radix or from_radix_c
+    InvalidRadix
+}
+
+pub use ParseError::*;
+
+// the most bits a decimal literal may need, counting the mantissa and log2(10) bits per unit of exponent
+pub const MAX_PARSE_BITS: usize = 1 << 18;
+
+struct Literal {
+    neg: bool,
+    // 10, or 2, 8, 16 for literals prefixed by 0b, 0o, 0x
+    radix: u32,
+    mantissa: Vec<Digit>,
+    // the value is mantissa * 10^exponent for decimal literals and mantissa * 2^exponent otherwise
+    exponent: isize,
+    significant_digits: usize
+}
+
+fn parse_literal(s: &str) -> Result<Literal, ParseError> {
+    if s.is_empty() {
+        return Err(Empty);
+    }
+    let (neg, s) = match s.as_bytes()[0] {
+        b'-' => (true, &s[1..]),
+        b'+' => (false, &s[1..]),
+        _ => (false, s)
+    };
+    let (radix, log2_radix, s) = match s.get(0..2) {
+        Some("0x") | Some("0X") => (16, 4, &s[2..]),
+        Some("0o") | Some("0O") => (8, 3, &s[2..]),
+        Some("0b") | Some("0B") => (2, 1, &s[2..]),
+        _ => (10, 0, s)
+    };
+    let bytes = s.as_bytes();
+    let mut on = 0;
+    // significant digits, most significant first
+    let mut digits: Vec<u32> = vec![];
+    let mut frac_len: usize = 0;
+    let mut seen_digit = false;
+    let mut seen_point = false;
+    while on < bytes.len() {
+        let b = bytes[on];
+        if let Some(d) = (b as char).to_digit(radix) {
+            seen_digit = true;
+            if seen_point {
+                frac_len += 1;
+            }
+            if d != 0 || !digits.is_empty() {
+                digits.push(d);
+            }
+        } else if b == b'.' && !seen_point {
+            seen_point = true;
+        } else if (radix == 10 && (b == b'e' || b == b'E')) || (radix != 10 && (b == b'p' || b == b'P')) {
+            break;
+        } else {
+            return Err(InvalidDigit);
+        }
+        on += 1;
+    }
+    if !seen_digit {
+        return Err(InvalidDigit);
+    }
+    let mut exponent: isize = 0;
+    if on < bytes.len() {
+        // skip the e or p
+        let exp_str = &s[(on + 1)..];
+        let digits = exp_str.strip_prefix(['+', '-']).unwrap_or(exp_str);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(InvalidExponent);
+        }
+        exponent = exp_str.parse().map_err(|_| InvalidExponent)?;
+    }
+    let frac_len = if radix == 10 {frac_len} else {frac_len.checked_mul(log2_radix).ok_or(InvalidExponent)?};
+    let exponent = exponent.checked_sub(Index::castsize(frac_len).map_err(|_| InvalidExponent)?).ok_or(InvalidExponent)?;
+    let significant_digits = digits.len();
+    digits.reverse();
+    let mantissa = from_radix_digits(&digits, radix);
+    Ok(Literal {
+        neg,
+        radix,
+        mantissa,
+        exponent,
+        significant_digits
+    })
+}
+
+impl BigFixed {
+    // Parse a decimal string like "-123.456e-7", rounding to the cutoff if the value has no terminating binary expansion. Values which do terminate
+    // are exact before the cutoff is applied. Hexadecimal, octal and binary literals like "-0x1a.8p+4" (p gives a power of two) always terminate.
+    pub fn parse_c(s: &str, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        let literal = parse_literal(s)?;
+        let mut returner = BigFixed::from_literal(literal, cutoff)?;
+        returner.cutoff(cutoff)?;
+        Ok(returner)
+    }
+
+    // exact for power of two radixes and terminating decimals, otherwise correctly rounded to cutoff (but possibly not yet cut off)
+    fn from_literal(literal: Literal, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        let Literal {neg, radix, mantissa, exponent, ..} = literal;
+        if radix != 10 {
+            let mut returner = BigFixed::construct(0, mantissa, Index::Bit(exponent))?;
+            if neg {
+                returner.negate()?;
+            }
+            return Ok(returner);
+        }
+        check_pow_bits(&mantissa, 10, exponent)?;
+        BigFixed::from_magnitude_pow10(neg, mantissa, 0, exponent, cutoff)
+    }
+}
+
+// Terminating binary expansions parse exactly. Anything else is rounded to the nearest value with enough significant bits to recover every given
+// decimal digit.
+impl FromStr for BigFixed {
+    type Err = BigFixedError;
+    fn from_str(s: &str) -> Result<BigFixed, BigFixedError> {
+        let literal = parse_literal(s)?;
+        // log2(10) < 10/3
+        let bits = Index::castsize(literal.significant_digits * 10 / 3 + DIGITBITS)?;
+        BigFixed::from_literal(literal, Cutoff {
+            fixed: None,
+            floating: Some(Index::Bit(bits)),
+            round: Rounding::Round
+        })
+    }
+}
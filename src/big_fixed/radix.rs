@@ -0,0 +1,139 @@
+/*
+    Conversion to and from native digits in an arbitrary radix. to_radix rounds x * radix^F to an integer, where F is max_frac_digits or fewer if
+    the expansion terminates sooner (it always does for even radixes), and then splits the integer into digits. from_radix is the inverse: exact
+    when radix^-point times the integer has a terminating binary expansion, rounded to a cutoff otherwise.
+
+    The digit splitting and combining in magnitude.rs is divide and conquer on powers radix^(2^k) so large values convert in subquadratic time.
+*/
+
+use crate::{Index, Cutoff, Rounding, BigFixed, BigFixedError, BigFixedParseError, big_fixed::{magnitude::*, parse::{Empty, InvalidDigit, InvalidRadix, TooLarge}}};
+
+use core::cmp::min;
+
+use alloc::vec::Vec;
+
+// Digits in some radix, least significant first as in to_digits, with value (-1)^neg * sum digits[i] * radix^(i - point). Zero has no digits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RadixDigits {
+    pub radix: u32,
+    pub neg: bool,
+    pub digits: Vec<u32>,
+    // the number of digits after the radix point
+    pub point: isize
+}
+
+impl RadixDigits {
+    // the digits narrowed to bytes, for radixes up to 256
+    pub fn digits_u8(&self) -> Option<Vec<u8>> {
+        if self.radix > 256 {
+            return None;
+        }
+        Some(self.digits.iter().map(|&d| d as u8).collect())
+    }
+
+    pub fn from_u8(radix: u32, neg: bool, digits: &[u8], point: isize) -> RadixDigits {
+        RadixDigits {
+            radix,
+            neg,
+            digits: digits.iter().map(|&d| d as u32).collect(),
+            point
+        }
+    }
+}
+
+impl BigFixed {
+    // self * radix^pow rounded to an integer
+    pub fn round_pow(&self, radix: u32, pow: isize, round: Rounding) -> Result<BigFixed, BigFixedError> {
+        let cutoff = Cutoff {
+            fixed: Some(Index::Position(0)),
+            floating: None,
+            round
+        };
+        let (mag, bit) = self.magnitude()?;
+        let mut returner = BigFixed::from_magnitude_pow(self.is_neg(), mag, bit, radix, pow, cutoff)?;
+        returner.cutoff(cutoff)?;
+        Ok(returner)
+    }
+
+    // The digits of self in radix rounded to at most max_frac_digits after the point. Trailing zeros after the point are dropped. Fails with
+    // InvalidRadix if radix is below 2.
+    pub fn to_radix(&self, radix: u32, max_frac_digits: usize, round: Rounding) -> Result<RadixDigits, BigFixedError> {
+        if radix < 2 {
+            return Err(BigFixedParseError(InvalidRadix));
+        }
+        let (_, bit) = self.magnitude()?;
+        let twos = radix.trailing_zeros() as usize;
+        let frac_digits = if bit >= 0 {
+            0
+        } else if twos > 0 {
+            // radix^F * 2^bit is an integer once twos * F >= -bit
+            min(max_frac_digits, Index::uncastsize(-bit)?.div_ceil(twos))
+        } else {
+            max_frac_digits
+        };
+        let scaled = self.round_pow(radix, Index::castsize(frac_digits)?, round)?;
+        let (mut mag, bit) = scaled.magnitude()?;
+        shl_bits(&mut mag, Index::saturating_unsigned(bit));
+        let mut digits = to_radix_digits(mag, radix);
+        let zeros = min(digits.iter().take_while(|&&d| d == 0).count(), frac_digits);
+        digits.drain(0..zeros);
+        let point = if digits.is_empty() {0} else {Index::castsize(frac_digits - zeros)?};
+        Ok(RadixDigits {
+            radix,
+            neg: scaled.is_neg(),
+            digits,
+            point
+        })
+    }
+
+    // The exact value of the digits. Fails with NonTerminating if it has no terminating binary expansion, which needs an odd factor in the radix.
+    pub fn from_radix(digits: &RadixDigits) -> Result<BigFixed, BigFixedError> {
+        BigFixed::from_radix_c(digits, Cutoff {
+            fixed: None,
+            floating: None,
+            round: Rounding::Round
+        })
+    }
+
+    // Parse digits in a radix from 2 to 36 with an optional sign and radix point, like "-1a.8" in radix 16. The value is exact so this fails with
+    // NonTerminating if it has no terminating binary expansion. Panics on a radix out of range, like from_str_radix on the primitive types.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigFixed, BigFixedError> {
+        assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+        let (neg, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s))
+        };
+        let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if int.is_empty() && frac.is_empty() {
+            return Err(BigFixedParseError(Empty));
+        }
+        let mut digits = Vec::with_capacity(int.len() + frac.len());
+        for c in int.chars().chain(frac.chars()).rev() {
+            digits.push(c.to_digit(radix).ok_or(BigFixedParseError(InvalidDigit))?);
+        }
+        BigFixed::from_radix(&RadixDigits {
+            radix,
+            neg,
+            digits,
+            point: Index::castsize(frac.len())?
+        })
+    }
+
+    // The value of the digits, exact if it terminates and correctly rounded to cutoff otherwise. Fails with InvalidRadix if the radix is below 2
+    // and with TooLarge if the point is too far out to build the value.
+    pub fn from_radix_c(digits: &RadixDigits, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        let RadixDigits {radix, neg, digits, point} = digits;
+        if *radix < 2 {
+            return Err(BigFixedParseError(InvalidRadix));
+        }
+        let pow = point.checked_neg().ok_or(BigFixedParseError(TooLarge))?;
+        if digits.iter().any(|d| d >= radix) {
+            return Err(BigFixedParseError(InvalidDigit));
+        }
+        let mag = from_radix_digits(digits, *radix);
+        check_pow_bits(&mag, *radix, *point)?;
+        let mut returner = BigFixed::from_magnitude_pow(*neg, mag, 0, *radix, pow, cutoff)?;
+        returner.cutoff(cutoff)?;
+        Ok(returner)
+    }
+}
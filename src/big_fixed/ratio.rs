@@ -0,0 +1,185 @@
+/*
+    Rational numbers. Every BigFixed is a dyadic rational m / 2^k so to_ratio is exact. from_ratio_c divides two BigFixeds with a single correct
+    rounding to the cutoff (exact when the quotient terminates in binary). div_assign is that division with no cutoff, so it is exact and fails when
+    the quotient does not terminate. rem_assign truncates the quotient to an integer and is always exact. Neither is an operator: x / 3 would have to
+    panic for most x, so / and % are left out as they always were, and with them num-traits' CheckedDiv and CheckedRem. checked_div and checked_rem
+    give the same Option results as inherent methods.
+
+    best_rational_approximation walks the continued fraction expansion of the exact value. The convergents p/q are the best approximations for
+    their denominators; once the next convergent's denominator would exceed the bound, the best remaining candidate is either the last convergent or
+    the semiconvergent (p0 + k p1) / (q0 + k q1) with the greatest k that fits, whichever is closer.
+*/
+
+use crate::{Index, Cutoff, Rounding, BigFixed, BigFixedError, big_fixed::magnitude::*, digit::*};
+
+use core::cmp::Ordering;
+
+use alloc::{vec::Vec, vec};
+
+// the integer neg * mag
+fn from_integer_magnitude(neg: bool, mag: Vec<Digit>) -> Result<BigFixed, BigFixedError> {
+    let mut returner = BigFixed::construct(0, mag, Index::Position(0))?;
+    if neg {
+        returner.negate()?;
+    }
+    Ok(returner)
+}
+
+// floor(|x|) as a magnitude
+fn integer_magnitude(x: &BigFixed) -> Result<Vec<Digit>, BigFixedError> {
+    let (mut mag, bit) = x.magnitude()?;
+    if bit >= 0 {
+        shl_bits(&mut mag, Index::uncastsize(bit)?);
+    } else {
+        shr_bits(&mut mag, Index::uncastsize(-bit)?);
+    }
+    Ok(mag)
+}
+
+// |a * b - c * d| along with whether a * b < c * d
+fn cross_difference(a: &[Digit], b: &[Digit], c: &[Digit], d: &[Digit]) -> (Vec<Digit>, bool) {
+    let mut left = mul_mag(a, b);
+    let mut right = mul_mag(c, d);
+    if cmp_mag(&left, &right) == Ordering::Less {
+        sub_mag(&mut right, &left, 0);
+        (right, true)
+    } else {
+        sub_mag(&mut left, &right, 0);
+        (left, false)
+    }
+}
+
+impl BigFixed {
+    // (numerator, denominator) with an integer numerator and a power of two denominator in lowest terms, so zero and integers have denominator 1
+    pub fn to_ratio(&self) -> Result<(BigFixed, BigFixed), BigFixedError> {
+        let (mut mag, bit) = self.magnitude()?;
+        let mut denominator = vec![1];
+        if bit >= 0 {
+            shl_bits(&mut mag, Index::uncastsize(bit)?);
+        } else {
+            shl_bits(&mut denominator, Index::uncastsize(-bit)?);
+        }
+        Ok((from_integer_magnitude(self.is_neg(), mag)?, from_integer_magnitude(false, denominator)?))
+    }
+
+    // num / den correctly rounded to cutoff, or exact if it terminates. Fails with BigFixedError::NonTerminating if it does not and the cutoff is
+    // unbounded, and with DivisionByZero if den is zero.
+    pub fn from_ratio_c(num: &BigFixed, den: &BigFixed, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        if den.is_zero() {
+            return Err(BigFixedError::DivisionByZero);
+        }
+        let neg = num.is_neg() != den.is_neg();
+        let (num_mag, num_bit) = num.magnitude()?;
+        let (den_mag, den_bit) = den.magnitude()?;
+        // both magnitudes are odd so the quotient terminates exactly when den_mag divides num_mag
+        let low_bit = num_bit - den_bit;
+        let (quot, rem) = divrem_mag(&num_mag, &den_mag);
+        let mut returner = if rem.is_empty() {
+            let mut exact = BigFixed::construct(0, quot, Index::Bit(low_bit))?;
+            if neg {
+                exact.negate()?;
+            }
+            exact
+        } else if cutoff.fixed.is_none() && cutoff.floating.is_none() {
+            return Err(BigFixedError::NonTerminating);
+        } else {
+            let divisor = Divisor::new(&den_mag);
+            let divide = |q: &mut Vec<Digit>| {
+                let (quot, rem) = divisor.divrem(q);
+                *q = quot;
+                !rem.is_empty()
+            };
+            BigFixed::round_quotient(neg, &num_mag, low_bit, Index::castsize(bit_len(&den_mag))?, divide, cutoff)?
+        };
+        returner.cutoff(cutoff)?;
+        Ok(returner)
+    }
+
+    // self / other exactly. Fails with NonTerminating if the quotient has no terminating binary expansion and with DivisionByZero if other is zero.
+    pub fn div_assign(&mut self, other: &BigFixed) -> Result<(), BigFixedError> {
+        *self = BigFixed::from_ratio_c(self, other, Cutoff {
+            fixed: None,
+            floating: None,
+            round: Rounding::Round
+        })?;
+        Ok(())
+    }
+
+    // self - other * q where q is self / other truncated to an integer, so the remainder takes the sign of self like % on the primitive types. It
+    // is always exact. Fails with DivisionByZero if other is zero.
+    pub fn rem_assign(&mut self, other: &BigFixed) -> Result<(), BigFixedError> {
+        let mut quotient = BigFixed::from_ratio_c(self, other, Cutoff {
+            fixed: Some(Index::Position(0)),
+            floating: None,
+            round: Rounding::TowardsZero
+        })?;
+        quotient.mul_assign(other)?;
+        self.sub_assign(&quotient)
+    }
+
+    // self / other exactly, or None if it does not terminate or other is zero
+    pub fn checked_div(&self, other: &BigFixed) -> Option<BigFixed> {
+        let mut returner = self.clone();
+        returner.div_assign(other).ok()?;
+        Some(returner)
+    }
+
+    // self % other as in rem_assign, or None if other is zero
+    pub fn checked_rem(&self, other: &BigFixed) -> Option<BigFixed> {
+        let mut returner = self.clone();
+        returner.rem_assign(other).ok()?;
+        Some(returner)
+    }
+
+    // The fraction (numerator, denominator) closest to self among those with denominator at most max_denominator. The denominator is positive
+    // and the fraction is in lowest terms. A max_denominator below 1 leaves only zero as a denominator and fails with DivisionByZero.
+    pub fn best_rational_approximation(&self, max_denominator: &BigFixed) -> Result<(BigFixed, BigFixed), BigFixedError> {
+        let bound = integer_magnitude(max_denominator)?;
+        if max_denominator.is_neg() || bound.is_empty() {
+            return Err(BigFixedError::DivisionByZero);
+        }
+        let neg = self.is_neg();
+        let (p, q) = self.to_ratio()?;
+        let p = integer_magnitude(&p)?;
+        let q = integer_magnitude(&q)?;
+        if cmp_mag(&q, &bound) != Ordering::Greater {
+            return Ok((from_integer_magnitude(neg, p)?, from_integer_magnitude(false, q)?));
+        }
+        // convergents p0/q0 and p1/q1 of |self| = p/q, with n/d the remaining complete quotient
+        let (mut p0, mut q0, mut p1, mut q1) = (vec![], vec![1], vec![1], vec![]);
+        let (mut n, mut d) = (p.clone(), q.clone());
+        loop {
+            let (a, r) = divrem_mag(&n, &d);
+            let mut q2 = mul_mag(&a, &q1);
+            add_mag(&mut q2, &q0, 0);
+            if cmp_mag(&q2, &bound) == Ordering::Greater {
+                break;
+            }
+            let mut p2 = mul_mag(&a, &p1);
+            add_mag(&mut p2, &p0, 0);
+            p0 = p1;
+            q0 = q1;
+            p1 = p2;
+            q1 = q2;
+            n = d;
+            d = r;
+        }
+        // the semiconvergent with the largest k keeping the denominator in bound
+        let mut room = bound;
+        sub_mag(&mut room, &q0, 0);
+        let k = divrem_mag(&room, &q1).0;
+        let mut semi_p = mul_mag(&k, &p1);
+        add_mag(&mut semi_p, &p0, 0);
+        let mut semi_q = mul_mag(&k, &q1);
+        add_mag(&mut semi_q, &q0, 0);
+        // compare |semi_p/semi_q - p/q| with |p1/q1 - p/q| scaled by q * semi_q * q1
+        let (semi_diff, _) = cross_difference(&semi_p, &q, &p, &semi_q);
+        let (conv_diff, _) = cross_difference(&p1, &q, &p, &q1);
+        let (p, q) = if cmp_mag(&mul_mag(&semi_diff, &q1), &mul_mag(&conv_diff, &semi_q)) == Ordering::Less {
+            (semi_p, semi_q)
+        } else {
+            (p1, q1)
+        };
+        Ok((from_integer_magnitude(neg, p)?, from_integer_magnitude(false, q)?))
+    }
+}
@@ -0,0 +1,256 @@
+/*
+    SCALE codec support behind the codec feature. A BigFixed encodes as its canonical bytes from to_bytes, which carry their own length, and a
+    CutoffBoundBigFixed as its scheme followed by its value. An Index is a one byte tag (0 for Position, 1 for Bit) followed by an i64.
+
+    Decoding reads the body length before the body so oversized payloads are rejected up front. The unbounded types refuse lengths longer than the
+    remaining input when the input knows it and otherwise read the body in chunks, so memory use never runs ahead of the bytes actually supplied.
+    BoundedBigFixed<N> and BoundedCutoffBoundBigFixed<N> cap the body at N bytes on both sides and implement MaxEncodedLen for runtime storage. They
+    also keep the whole body within MAX_PARSE_BITS of the binary point, checked from the header before the body is read, since a short body far from
+    the point (2^(8 * 2^40) is ten bytes) makes later arithmetic like adding 1 allocate everything in between.
+
+    Encoding fails only when the position in bytes does not fit in isize. Every decoded value and every bounded value encodes, and encoding any other
+    BigFixed that far out panics.
+*/
+
+use crate::{Index, CutoffScheme, CutoffBoundBigFixed, BigFixed, BigFixedError, BigFixedBytesError, MAX_PARSE_BITS, big_fixed::bytes::*};
+
+use codec::{Encode, EncodeLike, Decode, MaxEncodedLen, Input, Output, Error};
+
+use core::cmp::min;
+
+use alloc::vec::Vec;
+
+const CHUNK: usize = 4096;
+
+// the number of bytes a LEB128 varint of x takes
+const fn varint_len(mut x: u64) -> usize {
+    let mut returner = 1;
+    while x >= 0x80 {
+        x >>= 7;
+        returner += 1;
+    }
+    returner
+}
+
+fn describe(e: BigFixedError) -> Error {
+    Error::from(match e {
+        BigFixedBytesError(Truncated) => "BigFixed: truncated input",
+        BigFixedBytesError(InvalidHead) => "BigFixed: invalid head flag",
+        BigFixedBytesError(InvalidVarint) => "BigFixed: invalid varint",
+        BigFixedBytesError(NonCanonical) => "BigFixed: non canonical encoding",
+        BigFixedBytesError(TooLarge) => "BigFixed: body exceeds the size limit",
+        BigFixedBytesError(PositionOutOfRange) => "BigFixed: position out of range",
+        _ => "BigFixed: invalid encoding"
+    })
+}
+
+// the limits on a bounded encoding with the given byte position and body length
+fn check_bounded(position: isize, len: usize, max_body: usize) -> Result<(), BigFixedError> {
+    if len > max_body {
+        return Err(BigFixedBytesError(TooLarge));
+    }
+    let limit = Index::castsize(MAX_PARSE_BITS / 8)?;
+    let high = Index::castsize(len).ok().and_then(|len| position.checked_add(len));
+    if position < -limit || high.is_none_or(|high| high > limit) {
+        return Err(BigFixedBytesError(PositionOutOfRange));
+    }
+    Ok(())
+}
+
+// decode one BigFixed, with the limits of a bounded encoding with a body of at most max_body bytes if given
+fn decode_limited<I: Input>(input: &mut I, max_body: Option<usize>) -> Result<BigFixed, Error> {
+    let (head, position, len) = read_header(|| input.read_byte().map_err(|_| BigFixedBytesError(Truncated))).map_err(describe)?;
+    if let Some(max_body) = max_body {
+        check_bounded(position, len, max_body).map_err(describe)?;
+    }
+    if let Some(remaining) = input.remaining_len()? {
+        if len > remaining {
+            return Err(describe(BigFixedBytesError(Truncated)));
+        }
+    }
+    let mut body = Vec::with_capacity(min(len, CHUNK));
+    while body.len() < len {
+        let start = body.len();
+        body.resize(start + min(len - start, CHUNK), 0);
+        input.read(&mut body[start..])?;
+    }
+    from_parts(head, position, &body).map_err(describe)
+}
+
+impl Encode for Index {
+    fn size_hint(&self) -> usize {
+        9
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        let (tag, value) = match self {
+            Index::Position(x) => (0u8, *x),
+            Index::Bit(x) => (1u8, *x)
+        };
+        tag.encode_to(dest);
+        (value as i64).encode_to(dest);
+    }
+}
+
+impl EncodeLike for Index {}
+
+impl Decode for Index {
+    fn decode<I: Input>(input: &mut I) -> Result<Index, Error> {
+        let tag = u8::decode(input)?;
+        let value = isize::try_from(i64::decode(input)?).map_err(|_| "Index: value does not fit in isize")?;
+        match tag {
+            0 => Ok(Index::Position(value)),
+            1 => Ok(Index::Bit(value)),
+            _ => Err("Index: invalid variant".into())
+        }
+    }
+}
+
+impl MaxEncodedLen for Index {
+    fn max_encoded_len() -> usize {
+        9
+    }
+}
+
+impl Encode for BigFixed {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(&self.to_bytes().expect("BigFixed: position in bytes out of range"));
+    }
+}
+
+impl EncodeLike for BigFixed {}
+
+impl Decode for BigFixed {
+    fn decode<I: Input>(input: &mut I) -> Result<BigFixed, Error> {
+        decode_limited(input, None)
+    }
+}
+
+impl Encode for CutoffBoundBigFixed {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        self.scheme.encode_to(dest);
+        self.value.encode_to(dest);
+    }
+}
+
+impl EncodeLike for CutoffBoundBigFixed {}
+
+// decoded values are claimed by their scheme so they are cut off like any other CutoffBoundBigFixed
+impl Decode for CutoffBoundBigFixed {
+    fn decode<I: Input>(input: &mut I) -> Result<CutoffBoundBigFixed, Error> {
+        let scheme = CutoffScheme::decode(input)?;
+        let value = BigFixed::decode(input)?;
+        scheme.claim(value).map_err(describe)
+    }
+}
+
+// A BigFixed whose canonical body is at most N bytes, so its encoding has a known maximum length
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundedBigFixed<const N: usize>(BigFixed);
+
+impl<const N: usize> BoundedBigFixed<N> {
+    // fails with TooLarge if the body of x needs more than N bytes and with PositionOutOfRange if it reaches past MAX_PARSE_BITS
+    pub fn new(x: BigFixed) -> Result<BoundedBigFixed<N>, BigFixedError> {
+        let (_, position, body) = x.canonical_parts()?;
+        check_bounded(position, body.len(), N)?;
+        Ok(BoundedBigFixed(x))
+    }
+
+    pub fn get(&self) -> &BigFixed {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> BigFixed {
+        self.0
+    }
+}
+
+impl<const N: usize> TryFrom<BigFixed> for BoundedBigFixed<N> {
+    type Error = BigFixedError;
+    fn try_from(x: BigFixed) -> Result<BoundedBigFixed<N>, BigFixedError> {
+        BoundedBigFixed::new(x)
+    }
+}
+
+impl<const N: usize> From<BoundedBigFixed<N>> for BigFixed {
+    fn from(x: BoundedBigFixed<N>) -> BigFixed {
+        x.0
+    }
+}
+
+impl<const N: usize> Encode for BoundedBigFixed<N> {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        self.0.encode_to(dest);
+    }
+}
+
+impl<const N: usize> EncodeLike for BoundedBigFixed<N> {}
+
+impl<const N: usize> Decode for BoundedBigFixed<N> {
+    fn decode<I: Input>(input: &mut I) -> Result<BoundedBigFixed<N>, Error> {
+        Ok(BoundedBigFixed(decode_limited(input, Some(N))?))
+    }
+}
+
+impl<const N: usize> MaxEncodedLen for BoundedBigFixed<N> {
+    fn max_encoded_len() -> usize {
+        // head flag, zigzag i64 position, body length, body
+        1 + varint_len(u64::MAX) + varint_len(N as u64) + N
+    }
+}
+
+// A CutoffBoundBigFixed whose canonical body is at most N bytes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundedCutoffBoundBigFixed<const N: usize>(CutoffBoundBigFixed);
+
+impl<const N: usize> BoundedCutoffBoundBigFixed<N> {
+    // the same limits as BoundedBigFixed::new on x.value
+    pub fn new(x: CutoffBoundBigFixed) -> Result<BoundedCutoffBoundBigFixed<N>, BigFixedError> {
+        let (_, position, body) = x.value.canonical_parts()?;
+        check_bounded(position, body.len(), N)?;
+        Ok(BoundedCutoffBoundBigFixed(x))
+    }
+
+    pub fn get(&self) -> &CutoffBoundBigFixed {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> CutoffBoundBigFixed {
+        self.0
+    }
+}
+
+impl<const N: usize> TryFrom<CutoffBoundBigFixed> for BoundedCutoffBoundBigFixed<N> {
+    type Error = BigFixedError;
+    fn try_from(x: CutoffBoundBigFixed) -> Result<BoundedCutoffBoundBigFixed<N>, BigFixedError> {
+        BoundedCutoffBoundBigFixed::new(x)
+    }
+}
+
+impl<const N: usize> From<BoundedCutoffBoundBigFixed<N>> for CutoffBoundBigFixed {
+    fn from(x: BoundedCutoffBoundBigFixed<N>) -> CutoffBoundBigFixed {
+        x.0
+    }
+}
+
+impl<const N: usize> Encode for BoundedCutoffBoundBigFixed<N> {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        self.0.encode_to(dest);
+    }
+}
+
+impl<const N: usize> EncodeLike for BoundedCutoffBoundBigFixed<N> {}
+
+impl<const N: usize> Decode for BoundedCutoffBoundBigFixed<N> {
+    fn decode<I: Input>(input: &mut I) -> Result<BoundedCutoffBoundBigFixed<N>, Error> {
+        let scheme = CutoffScheme::decode(input)?;
+        let value = decode_limited(input, Some(N))?;
+        BoundedCutoffBoundBigFixed::new(scheme.claim(value).map_err(describe)?).map_err(describe)
+    }
+}
+
+impl<const N: usize> MaxEncodedLen for BoundedCutoffBoundBigFixed<N> {
+    fn max_encoded_len() -> usize {
+        CutoffScheme::max_encoded_len() + BoundedBigFixed::<N>::max_encoded_len()
+    }
+}
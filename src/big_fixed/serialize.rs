@@ -0,0 +1,84 @@
+/*
+    Serde support behind the serde feature. Human readable formats like JSON get the exact decimal string from Display, and deserializing accepts any
+    string parse_c takes exactly (decimal, or 0x/0o/0b literals with a p exponent) but rejects values which would need rounding. Binary formats get the
+    canonical encoding from to_bytes.
+
+    Deserialized input is untrusted, so strings longer than MAX_PARSE_BITS are refused before parsing and every value, string or bytes, must have all of
+    its nonzero bits within MAX_PARSE_BITS of the binary point. A tiny literal like "0x1p999999999999" would otherwise decode into a value which no
+    later operation can afford to touch. Byte sequences are preallocated no further than serde's own cautious limit.
+*/
+
+use crate::{Index, BigFixed, BigFixedError, BigFixedParseError, ParseError, Cutoff, Rounding, MAX_PARSE_BITS, big_fixed::magnitude::bit_len};
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de::{self, Visitor, SeqAccess}};
+
+use core::{fmt, cmp::min};
+
+use alloc::{vec::Vec, format};
+
+impl Serialize for BigFixed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes().map_err(|e| serde::ser::Error::custom(format!("{:?}", e)))?)
+        }
+    }
+}
+
+// the most bytes visit_seq reserves ahead of the elements actually arriving, as in serde's size_hint::cautious
+const MAX_PREALLOCATION: usize = 1 << 20;
+
+// x unless it has a nonzero bit more than MAX_PARSE_BITS away from the binary point
+fn within_limits(x: BigFixed) -> Result<BigFixed, BigFixedError> {
+    let (mag, bit) = x.magnitude()?;
+    let limit = Index::castsize(MAX_PARSE_BITS)?;
+    if !mag.is_empty() && (bit < -limit || bit > limit - Index::castsize(bit_len(&mag))?) {
+        return Err(BigFixedParseError(ParseError::TooLarge));
+    }
+    Ok(x)
+}
+
+struct BigFixedVisitor;
+
+impl<'de> Visitor<'de> for BigFixedVisitor {
+    type Value = BigFixed;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an exact number string or canonical BigFixed bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<BigFixed, E> {
+        if s.len() > MAX_PARSE_BITS {
+            return Err(E::custom(format!("{:?}", BigFixedParseError(ParseError::TooLarge))));
+        }
+        BigFixed::parse_c(s, Cutoff {
+            fixed: None,
+            floating: None,
+            round: Rounding::Round
+        }).and_then(within_limits).map_err(|e| E::custom(format!("{:?}", e)))
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<BigFixed, E> {
+        BigFixed::from_bytes(bytes).and_then(within_limits).map_err(|e| E::custom(format!("{:?}", e)))
+    }
+
+    // some binary formats hand bytes over as a sequence
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BigFixed, A::Error> {
+        let mut bytes = Vec::with_capacity(min(seq.size_hint().unwrap_or(0), MAX_PREALLOCATION));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigFixed {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BigFixed, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BigFixedVisitor)
+        } else {
+            deserializer.deserialize_bytes(BigFixedVisitor)
+        }
+    }
+}
@@ -0,0 +1,128 @@
+/*
+    Sums, products and inner products. Sum and Product for BigFixed are exact. sum_s and product_s take the scheme explicitly, combine the values
+    exactly and claim the result once, so there is a single rounding rather than one per step. An empty sum is zero and an empty product is one
+    under that scheme.
+
+    Sum and Product for CutoffBoundBigFixed collect into a Result. They use the scheme of the first value, as the binary operators use the scheme
+    of their left operand, and otherwise work like sum_s and product_s. An empty iterator has no scheme to bind the result to and fails with
+    EmptyIterator.
+
+    sum_c and dot_c accumulate exactly and round once to the cutoff, which makes them correctly rounded: the result is the exact sum (or inner
+    product) cut off, whatever the order or the cancellation along the way.
+*/
+
+use crate::{Cutoff, BigFixed, BigFixedError, CutoffScheme, CutoffBoundBigFixed};
+
+use core::{iter::{Sum, Product}, borrow::Borrow};
+
+impl BigFixed {
+    // start op= x for every x
+    fn fold_exact<B: Borrow<BigFixed>, I: Iterator<Item = B>>(
+        iter: I, start: BigFixed, op: fn(&mut BigFixed, &BigFixed) -> Result<(), BigFixedError>
+    ) -> Result<BigFixed, BigFixedError> {
+        let mut returner = start;
+        for x in iter {
+            op(&mut returner, x.borrow())?;
+        }
+        Ok(returner)
+    }
+
+    pub fn sum_c<B: Borrow<BigFixed>, I: IntoIterator<Item = B>>(iter: I, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        let mut returner = BigFixed::fold_exact(iter.into_iter(), BigFixed::ZERO, BigFixed::add_assign)?;
+        returner.cutoff(cutoff)?;
+        Ok(returner)
+    }
+
+    // the sum of a[i] * b[i], correctly rounded. Panics if the lengths differ.
+    pub fn dot_c(a: &[BigFixed], b: &[BigFixed], cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        assert_eq!(a.len(), b.len(), "dot product of slices with different lengths");
+        let mut returner = BigFixed::ZERO;
+        // one buffer reused for every term
+        let mut term = BigFixed::ZERO;
+        for (x, y) in a.iter().zip(b.iter()) {
+            term.overwrite(x);
+            term.mul_assign(y)?;
+            returner.add_assign(&term)?;
+        }
+        returner.cutoff(cutoff)?;
+        Ok(returner)
+    }
+}
+
+impl Sum for BigFixed {
+    fn sum<I: Iterator<Item = BigFixed>>(iter: I) -> BigFixed {
+        BigFixed::fold_exact(iter, BigFixed::ZERO, BigFixed::add_assign).unwrap()
+    }
+}
+
+impl<'a> Sum<&'a BigFixed> for BigFixed {
+    fn sum<I: Iterator<Item = &'a BigFixed>>(iter: I) -> BigFixed {
+        BigFixed::fold_exact(iter, BigFixed::ZERO, BigFixed::add_assign).unwrap()
+    }
+}
+
+impl Product for BigFixed {
+    fn product<I: Iterator<Item = BigFixed>>(iter: I) -> BigFixed {
+        BigFixed::fold_exact(iter, BigFixed::from(1), BigFixed::mul_assign).unwrap()
+    }
+}
+
+impl<'a> Product<&'a BigFixed> for BigFixed {
+    fn product<I: Iterator<Item = &'a BigFixed>>(iter: I) -> BigFixed {
+        BigFixed::fold_exact(iter, BigFixed::from(1), BigFixed::mul_assign).unwrap()
+    }
+}
+
+impl CutoffBoundBigFixed {
+    // the values combined exactly, then claimed by scheme
+    fn fold_bound<B: Borrow<CutoffBoundBigFixed>, I: IntoIterator<Item = B>>(
+        iter: I, start: BigFixed, op: fn(&mut BigFixed, &BigFixed) -> Result<(), BigFixedError>, scheme: CutoffScheme
+    ) -> Result<CutoffBoundBigFixed, BigFixedError> {
+        let mut returner = start;
+        for x in iter {
+            op(&mut returner, &x.borrow().value)?;
+        }
+        scheme.claim(returner)
+    }
+
+    pub fn sum_s<B: Borrow<CutoffBoundBigFixed>, I: IntoIterator<Item = B>>(iter: I, scheme: CutoffScheme) -> Result<CutoffBoundBigFixed, BigFixedError> {
+        CutoffBoundBigFixed::fold_bound(iter, BigFixed::ZERO, BigFixed::add_assign, scheme)
+    }
+
+    pub fn product_s<B: Borrow<CutoffBoundBigFixed>, I: IntoIterator<Item = B>>(iter: I, scheme: CutoffScheme) -> Result<CutoffBoundBigFixed, BigFixedError> {
+        CutoffBoundBigFixed::fold_bound(iter, BigFixed::from(1), BigFixed::mul_assign, scheme)
+    }
+
+    // fold_bound under the scheme of the first value
+    fn fold_first<B: Borrow<CutoffBoundBigFixed>, I: Iterator<Item = B>>(
+        mut iter: I, op: fn(&mut BigFixed, &BigFixed) -> Result<(), BigFixedError>
+    ) -> Result<CutoffBoundBigFixed, BigFixedError> {
+        let first = iter.next().ok_or(BigFixedError::EmptyIterator)?;
+        let first = first.borrow();
+        CutoffBoundBigFixed::fold_bound(iter, first.value.clone(), op, first.scheme)
+    }
+}
+
+impl Sum<CutoffBoundBigFixed> for Result<CutoffBoundBigFixed, BigFixedError> {
+    fn sum<I: Iterator<Item = CutoffBoundBigFixed>>(iter: I) -> Result<CutoffBoundBigFixed, BigFixedError> {
+        CutoffBoundBigFixed::fold_first(iter, BigFixed::add_assign)
+    }
+}
+
+impl<'a> Sum<&'a CutoffBoundBigFixed> for Result<CutoffBoundBigFixed, BigFixedError> {
+    fn sum<I: Iterator<Item = &'a CutoffBoundBigFixed>>(iter: I) -> Result<CutoffBoundBigFixed, BigFixedError> {
+        CutoffBoundBigFixed::fold_first(iter, BigFixed::add_assign)
+    }
+}
+
+impl Product<CutoffBoundBigFixed> for Result<CutoffBoundBigFixed, BigFixedError> {
+    fn product<I: Iterator<Item = CutoffBoundBigFixed>>(iter: I) -> Result<CutoffBoundBigFixed, BigFixedError> {
+        CutoffBoundBigFixed::fold_first(iter, BigFixed::mul_assign)
+    }
+}
+
+impl<'a> Product<&'a CutoffBoundBigFixed> for Result<CutoffBoundBigFixed, BigFixedError> {
+    fn product<I: Iterator<Item = &'a CutoffBoundBigFixed>>(iter: I) -> Result<CutoffBoundBigFixed, BigFixedError> {
+        CutoffBoundBigFixed::fold_first(iter, BigFixed::mul_assign)
+    }
+}
@@ -19,9 +19,11 @@
 
 use crate::{Index, BigFixedError};
 
-use std::{cmp::{PartialEq}, fmt};
+use core::{cmp::{PartialEq}, fmt};
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "codec", derive(codec::Encode, codec::Decode, codec::MaxEncodedLen))]
 pub enum Rounding {
     Floor,
     Ceiling,
@@ -31,6 +33,8 @@ pub enum Rounding {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "codec", derive(codec::Encode, codec::Decode, codec::MaxEncodedLen))]
 pub struct Cutoff {
     pub fixed: Option<Index>,
     pub floating: Option<Index>,
@@ -1,8 +1,8 @@
-pub use crate::{digit::*, Index, Cutoff, BigFixed, BigFixedError, scheme_op};
+pub use crate::{digit::*, Index, Cutoff, BigFixed, Body, BigFixedError, scheme_op};
 
 use paste::paste;
 
-use std::{
+use core::{
     ops::{
         Add, AddAssign,
         BitAnd, BitAndAssign,
@@ -20,12 +20,15 @@ use std::{
 };
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "codec", derive(codec::Encode, codec::Decode, codec::MaxEncodedLen))]
 pub struct CutoffScheme {
     pub comparisons: Cutoff,
     pub arithmetic: Cutoff,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CutoffBoundBigFixed {
     pub scheme: CutoffScheme,
     pub value: BigFixed
@@ -46,7 +49,7 @@ impl CutoffScheme {
 }
 
 impl CutoffScheme {
-    pub fn construct(&self, head: Digit, body: Vec<Digit>, position: Index) -> Result<CutoffBoundBigFixed, BigFixedError> {
+    pub fn construct<B: Into<Body>>(&self, head: Digit, body: B, position: Index) -> Result<CutoffBoundBigFixed, BigFixedError> {
         Ok(
             CutoffBoundBigFixed {
                 scheme: *self,
@@ -254,6 +257,21 @@ impl Div<CutoffBoundBigFixed> for BigFixed {
     }
 }
 
+// deserialized values are claimed by their scheme so they are cut off like any other CutoffBoundBigFixed
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CutoffBoundBigFixed {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<CutoffBoundBigFixed, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "CutoffBoundBigFixed")]
+        struct Fields {
+            scheme: CutoffScheme,
+            value: BigFixed
+        }
+        let Fields {scheme, value} = Fields::deserialize(deserializer)?;
+        scheme.claim(value).map_err(|e| serde::de::Error::custom(alloc::format!("{:?}", e)))
+    }
+}
+
 impl PartialEq<BigFixed> for CutoffBoundBigFixed {
     fn eq(&self, other: &BigFixed) -> bool {
         self.value.full_eq_c(other, self.scheme.comparisons).unwrap()
@@ -280,15 +298,62 @@ impl PartialOrd for CutoffBoundBigFixed {
     }
 }
 
+impl CutoffBoundBigFixed {
+    // The value to format: the stored value when the formatter has a precision, so {:.N} rounds once from it in the comparison direction, and the
+    // value cut off by the comparison cutoff otherwise.
+    fn display_value(&self, f: &fmt::Formatter) -> Result<BigFixed, BigFixedError> {
+        let mut value = self.value.clone();
+        if f.precision().is_none() {
+            value.cutoff(self.scheme.comparisons)?;
+        }
+        Ok(value)
+    }
+}
+
+// Displays the value as seen by comparisons. Without a precision it is the shortest decimal which parses back to the same value under the
+// comparison cutoff, so a value from 0.1f64 shows as 0.1. With {:.N} the stored value is rounded once to N places in the comparison direction.
 impl fmt::Display for CutoffBoundBigFixed {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.value)
+        if f.precision().is_none() {
+            let (digits, neg) = self.value.shortest_decimal_parts(self.scheme.comparisons).map_err(|_| fmt::Error)?;
+            return f.pad_integral(!neg, "", &digits);
+        }
+        self.value.fmt_decimal(f, self.scheme.comparisons.round)
+    }
+}
+
+impl fmt::LowerExp for CutoffBoundBigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_value(f).map_err(|_| fmt::Error)?.fmt_scientific(f, self.scheme.comparisons.round, 'e')
+    }
+}
+
+impl fmt::UpperExp for CutoffBoundBigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_value(f).map_err(|_| fmt::Error)?.fmt_scientific(f, self.scheme.comparisons.round, 'E')
     }
 }
 
 impl fmt::Binary for CutoffBoundBigFixed {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:b}", self.value)
+        fmt::Binary::fmt(&self.value, f)
+    }
+}
+
+impl fmt::Octal for CutoffBoundBigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Octal::fmt(&self.value, f)
     }
 }
 
+impl fmt::LowerHex for CutoffBoundBigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.value, f)
+    }
+}
+
+impl fmt::UpperHex for CutoffBoundBigFixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.value, f)
+    }
+}
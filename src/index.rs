@@ -1,6 +1,6 @@
 // BigFixeds can be indexed by position (wrt Digit) or bit. If bit precision is not possible it may convert to the corresponding position index.
 
-pub use std::{
+pub use core::{
     convert::{
         From,
         TryFrom
@@ -21,15 +21,16 @@ pub use std::{
     },
     num::{
         TryFromIntError
-    },
-    error::{
-        Error
     }
 };
 
+#[cfg(feature = "std")]
+pub use std::error::Error;
+
 use crate::{digit::*, macros::*};
 
 #[derive(Clone, Copy, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Index {
     Position(isize),
     Bit(isize)
@@ -50,6 +51,7 @@ impl fmt::Display for IndexError {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for IndexError {}
 
 impl From<TryFromIntError> for IndexError {
@@ -126,14 +128,15 @@ impl Index {
     }
 
     pub fn bit_value(&self) -> Result<isize, IndexError> {
-        return Ok(self.cast_to_bit()?.value())
+        Ok(self.cast_to_bit()?.value())
     }
 
     pub fn position_value(&self) -> isize {
-        return self.cast_to_position().value()
+        self.cast_to_position().value()
     }
     
-    pub fn neg(self) -> Result<Index, IndexError> {
+    // -self, failing on overflow. -x calls this through Neg.
+    pub fn negate(self) -> Result<Index, IndexError> {
         match self {
             Position(x) => Ok(Position(x.checked_neg().ok_or(IntegerCastOverflow)?)),
             Bit(x) => Ok(Bit(x.checked_neg().ok_or(IntegerCastOverflow)?))
@@ -200,7 +203,7 @@ impl From<Index> for usize {
     }
 }
 
-unary_copy!(Neg, neg, Index, neg, Index, IndexError);
+unary_copy!(Neg, neg, Index, negate, Index, IndexError);
 
 impl Add for &Index {
     type Output = Result<Index, IndexError>;
@@ -280,7 +283,7 @@ impl Add<&Index> for usize {
 impl Add<Index> for usize {
     type Output = Result<Index, IndexError>;
     fn add(self, other: Index) -> Result<Index, IndexError> {
-        self + &other
+        <Self as Add<&Index>>::add(self, &other)
     }
 }
 
@@ -318,7 +321,7 @@ impl Add<&Index> for isize {
 impl Add<Index> for isize {
     type Output = Result<Index, IndexError>;
     fn add(self, other: Index) -> Result<Index, IndexError> {
-        self + &other
+        <Self as Add<&Index>>::add(self, &other)
     }
 }
 
@@ -400,7 +403,7 @@ impl Sub<&Index> for usize {
 impl Sub<Index> for usize {
     type Output = Result<Index, IndexError>;
     fn sub(self, other: Index) -> Result<Index, IndexError> {
-        self - &other
+        <Self as Sub<&Index>>::sub(self, &other)
     }
 }
 
@@ -438,7 +441,7 @@ impl Sub<&Index> for isize {
 impl Sub<Index> for isize {
     type Output = Result<Index, IndexError>;
     fn sub(self, other: Index) -> Result<Index, IndexError> {
-        self - &other
+        <Self as Sub<&Index>>::sub(self, &other)
     }
 }
 
@@ -520,7 +523,7 @@ impl Mul<&Index> for usize {
 impl Mul<Index> for usize {
     type Output = Result<Index, IndexError>;
     fn mul(self, other: Index) -> Result<Index, IndexError> {
-        self * &other
+        <Self as Mul<&Index>>::mul(self, &other)
     }
 }
 
@@ -558,22 +561,14 @@ impl Mul<&Index> for isize {
 impl Mul<Index> for isize {
     type Output = Result<Index, IndexError>;
     fn mul(self, other: Index) -> Result<Index, IndexError> {
-        self * &other
+        <Self as Mul<&Index>>::mul(self, &other)
     }
 }
 
+// equal when they name the same bit, agreeing with Ord
 impl PartialEq for Index {
     fn eq(&self, other: &Index) -> bool {
-        match (self, other) {
-            (Position(x), Position(y)) => x == y,
-            (Bit(x), Bit(y)) => x == y,
-            (Position(x), Bit(y)) => {
-                *x == Index::bit_to_position(*y)
-            },
-            (Bit(x), Position(y)) => {
-                *y == Index::bit_to_position(*x)
-            }
-        }
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -598,28 +593,25 @@ impl PartialEq<usize> for Index {
 
 impl PartialOrd for Index {
     fn partial_cmp(&self, other: &Index) -> Option<Ordering> {
-        match (self, other) {
-            (Position(x), Position(y)) => x.partial_cmp(y),
-            (Bit(x), Bit(y)) => x.partial_cmp(y),
-            (Position(x), Bit(y)) => {
-                match Index::position_to_bit(*x) {
-                    Ok(z) => z.partial_cmp(y),
-                    Err(_) => None
-                }
-            },
-            (Bit(x), Position(y)) => {
-                match Index::position_to_bit(*y) {
-                    Ok(z) => x.partial_cmp(&z),
-                    Err(_) => None
-                }
-            }
-        }
+        Some(self.cmp(other))
     }
 }
 
+// by value, so Position(0) < Bit(5). A position whose bit value overflows isize is beyond every Bit.
 impl Ord for Index {
     fn cmp(&self, other: &Index) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        match (self, other) {
+            (Position(x), Position(y)) => x.cmp(y),
+            (Bit(x), Bit(y)) => x.cmp(y),
+            (Position(x), Bit(y)) => match Index::position_to_bit(*x) {
+                Ok(z) => z.cmp(y),
+                Err(_) => x.cmp(&0)
+            },
+            (Bit(x), Position(y)) => match Index::position_to_bit(*y) {
+                Ok(z) => x.cmp(&z),
+                Err(_) => 0.cmp(y)
+            }
+        }
     }
 }
 
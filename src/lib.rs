@@ -1,3 +1,7 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod macros;
 
 pub mod digit;
@@ -16,3 +20,6 @@ pub mod cutoff_scheme;
 pub use crate::cutoff_scheme::*;
 
 pub mod schemes;
+
+pub mod polynomial;
+pub use crate::polynomial::*;
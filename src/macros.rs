@@ -120,20 +120,14 @@ pub(crate) use op_assign_to_op;
 
 #[macro_export]
 macro_rules! cutoff_op {
-    // op_assign_to_op
+    // only the operator impls, for types which write their own op_c and op_assign_c
     (
+        impls;
         $op: ident, $op_fn_name: ident, $op_c_fn_name: ident,
         $op_assign: ident, $op_assign_fn_name: ident, $op_assign_c_fn_name: ident,
-        $self_type: ty, $other_type: ty, $cutoff_type: ty, $cutoff_fn_name: ident,
-        $result_type: ty, $error_type: ty
+        $self_type: ty, $other_type: ty, $cutoff_type: ty,
+        $result_type: ty
     ) => {
-        // &a += &b
-        impl $self_type {
-            pub fn $op_assign_c_fn_name(&mut self, other: &$other_type, cutoff: $cutoff_type) -> Result<(), $error_type> {
-                self.$op_assign_fn_name(other)?;
-                self.$cutoff_fn_name(cutoff)
-            }
-        }
         // a += &b
         impl $op_assign<(&$other_type, $cutoff_type)> for $self_type {
             fn $op_assign_fn_name(&mut self, (other, cutoff): (&$other_type, $cutoff_type)) {
@@ -148,15 +142,6 @@ macro_rules! cutoff_op {
             }
         }
         
-        // a +. &b
-        impl $self_type {
-            pub fn $op_c_fn_name(&self, other: &$other_type, cutoff: $cutoff_type) -> Result<$result_type, $error_type> {
-                let mut res = self.$op_fn_name(other)?;
-                res.cutoff(cutoff)?;
-                Ok(res)
-            }
-        }
-
         // &a + &b
         impl $op<(&$other_type, $cutoff_type)> for &$self_type {
             type Output = $result_type;
@@ -189,6 +174,38 @@ macro_rules! cutoff_op {
             }
         }
     };
+    // op_assign_to_op
+    (
+        $op: ident, $op_fn_name: ident, $op_c_fn_name: ident,
+        $op_assign: ident, $op_assign_fn_name: ident, $op_assign_c_fn_name: ident,
+        $self_type: ty, $other_type: ty, $cutoff_type: ty, $cutoff_fn_name: ident,
+        $result_type: ty, $error_type: ty
+    ) => {
+        // &a += &b
+        impl $self_type {
+            pub fn $op_assign_c_fn_name(&mut self, other: &$other_type, cutoff: $cutoff_type) -> Result<(), $error_type> {
+                self.$op_assign_fn_name(other)?;
+                self.$cutoff_fn_name(cutoff)
+            }
+        }
+
+        // a +. &b
+        impl $self_type {
+            pub fn $op_c_fn_name(&self, other: &$other_type, cutoff: $cutoff_type) -> Result<$result_type, $error_type> {
+                let mut res = self.$op_fn_name(other)?;
+                res.cutoff(cutoff)?;
+                Ok(res)
+            }
+        }
+
+        cutoff_op!(
+            impls;
+            $op, $op_fn_name, $op_c_fn_name,
+            $op_assign, $op_assign_fn_name, $op_assign_c_fn_name,
+            $self_type, $other_type, $cutoff_type,
+            $result_type
+        );
+    };
     // unary with extension
     (
         $op: ty, $op_fn_name: ident,
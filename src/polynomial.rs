@@ -0,0 +1,179 @@
+/*
+    Polynomials with BigFixed coefficients and rational functions of them. Coefficients are stored lowest degree first with trailing zeros trimmed,
+    so the zero polynomial has no coefficients and equal polynomials have equal coefficient lists.
+
+    Arithmetic on polynomials (addition, subtraction, multiplication, derivative, composition) is exact. eval is exact Horner; eval_c is Horner with
+    a fused multiply-add at every step, so each step rounds once to the cutoff and the result matches a fixed precision Horner evaluation with fma
+    bit for bit. RationalFunction::eval_c evaluates the numerator and denominator with eval_c and divides them with a single correct rounding.
+*/
+
+use crate::{Cutoff, BigFixed, BigFixedError, op_assign_to_op};
+
+use core::{
+    ops::{
+        Add, AddAssign,
+        Mul, MulAssign,
+        Sub, SubAssign
+    },
+    cmp::max
+};
+
+use alloc::vec::Vec;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Polynomial {
+    coefficients: Vec<BigFixed>
+}
+
+impl Polynomial {
+    // coefficients[i] multiplies x^i
+    pub fn new(coefficients: Vec<BigFixed>) -> Polynomial {
+        let mut returner = Polynomial {
+            coefficients
+        };
+        returner.trim();
+        returner
+    }
+
+    pub fn zero() -> Polynomial {
+        Polynomial {
+            coefficients: Vec::new()
+        }
+    }
+
+    pub fn constant(c: BigFixed) -> Polynomial {
+        Polynomial::new(alloc::vec![c])
+    }
+
+    fn trim(&mut self) {
+        while self.coefficients.last().is_some_and(|c| c.is_zero()) {
+            self.coefficients.pop();
+        }
+    }
+
+    pub fn coefficients(&self) -> &[BigFixed] {
+        &self.coefficients
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    // None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn eval(&self, x: &BigFixed) -> Result<BigFixed, BigFixedError> {
+        let mut returner = BigFixed::ZERO;
+        for c in self.coefficients.iter().rev() {
+            returner.mul_assign(x)?;
+            returner.add_assign(c)?;
+        }
+        Ok(returner)
+    }
+
+    // Horner's rule, rounding once per step
+    pub fn eval_c(&self, x: &BigFixed, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        let mut coefficients = self.coefficients.iter().rev();
+        let mut returner = match coefficients.next() {
+            Some(c) => c.clone(),
+            None => return Ok(BigFixed::ZERO)
+        };
+        returner.cutoff(cutoff)?;
+        for c in coefficients {
+            returner.mul_add_assign_c(x, c, cutoff)?;
+        }
+        Ok(returner)
+    }
+
+    pub fn derivative(&self) -> Result<Polynomial, BigFixedError> {
+        let mut coefficients = Vec::with_capacity(self.coefficients.len().saturating_sub(1));
+        for (i, c) in self.coefficients.iter().enumerate().skip(1) {
+            coefficients.push(c.mul(&BigFixed::from(i))?);
+        }
+        Ok(Polynomial::new(coefficients))
+    }
+
+    // self(inner(x))
+    pub fn compose(&self, inner: &Polynomial) -> Result<Polynomial, BigFixedError> {
+        let mut returner = Polynomial::zero();
+        for c in self.coefficients.iter().rev() {
+            returner.mul_assign(inner)?;
+            returner.add_assign(&Polynomial::constant(c.clone()))?;
+        }
+        Ok(returner)
+    }
+
+    pub fn add_assign(&mut self, other: &Polynomial) -> Result<(), BigFixedError> {
+        let len = max(self.coefficients.len(), other.coefficients.len());
+        self.coefficients.resize(len, BigFixed::ZERO);
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients.iter()) {
+            a.add_assign(b)?;
+        }
+        self.trim();
+        Ok(())
+    }
+
+    pub fn sub_assign(&mut self, other: &Polynomial) -> Result<(), BigFixedError> {
+        let len = max(self.coefficients.len(), other.coefficients.len());
+        self.coefficients.resize(len, BigFixed::ZERO);
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients.iter()) {
+            a.sub_assign(b)?;
+        }
+        self.trim();
+        Ok(())
+    }
+
+    pub fn mul_assign(&mut self, other: &Polynomial) -> Result<(), BigFixedError> {
+        if self.is_zero() || other.is_zero() {
+            self.coefficients.clear();
+            return Ok(());
+        }
+        let mut coefficients = alloc::vec![BigFixed::ZERO; self.coefficients.len() + other.coefficients.len() - 1];
+        // one buffer reused for every term
+        let mut term = BigFixed::ZERO;
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                term.overwrite(a);
+                term.mul_assign(b)?;
+                coefficients[i + j].add_assign(&term)?;
+            }
+        }
+        self.coefficients = coefficients;
+        // leading coefficients are nonzero so their product is too
+        Ok(())
+    }
+}
+
+op_assign_to_op!(Add, add, AddAssign, add_assign, Polynomial, Polynomial, Polynomial, BigFixedError);
+op_assign_to_op!(Sub, sub, SubAssign, sub_assign, Polynomial, Polynomial, Polynomial, BigFixedError);
+op_assign_to_op!(Mul, mul, MulAssign, mul_assign, Polynomial, Polynomial, Polynomial, BigFixedError);
+
+#[derive(Clone, Debug)]
+pub struct RationalFunction {
+    pub numerator: Polynomial,
+    pub denominator: Polynomial
+}
+
+impl RationalFunction {
+    // panics if the denominator is the zero polynomial
+    pub fn new(numerator: Polynomial, denominator: Polynomial) -> RationalFunction {
+        assert!(!denominator.is_zero(), "zero denominator");
+        RationalFunction {
+            numerator,
+            denominator
+        }
+    }
+
+    // The numerator and denominator by eval_c, then their quotient correctly rounded. Fails with DivisionByZero at a pole, or wherever the
+    // denominator rounds to zero.
+    pub fn eval_c(&self, x: &BigFixed, cutoff: Cutoff) -> Result<BigFixed, BigFixedError> {
+        let num = self.numerator.eval_c(x, cutoff)?;
+        let den = self.denominator.eval_c(x, cutoff)?;
+        if den.is_zero() {
+            return Err(BigFixedError::DivisionByZero);
+        }
+        BigFixed::from_ratio_c(&num, &den, cutoff)
+    }
+}
@@ -0,0 +1,117 @@
+use bigfixed::{digit::*, Index, BigFixed, BigFixedError, ArrayBigFixed, ArrayError, BigFixedArrayError};
+
+fn random(seed: &mut u64) -> BigFixed {
+    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    let x = BigFixed::from((*seed >> 8) as i64 >> (*seed % 56));
+    x.shift(Index::Bit((*seed % 64) as isize - 32)).unwrap()
+}
+
+#[test]
+fn conversions() {
+    let x = BigFixed::try_from(-1234.5625).unwrap();
+    let a = ArrayBigFixed::<4>::try_from(&x).unwrap();
+    assert_eq!(a.head, x.head, "head");
+    assert_eq!(a.body(), x.body.as_slice(), "body");
+    assert_eq!(a.position, x.position, "position");
+    assert_eq!(BigFixed::from(a), x, "back");
+    assert_eq!(ArrayBigFixed::<1>::try_from(&x), Err(BigFixedArrayError(ArrayError::CapacityExceeded)), "too small");
+    // head only values need no capacity at all
+    assert_eq!(BigFixed::from(ArrayBigFixed::<0>::try_from(BigFixed::from(-65536)).unwrap()), BigFixed::from(-65536), "head only");
+    assert!(ArrayBigFixed::<0>::try_from(BigFixed::from(0)).unwrap().is_zero(), "zero");
+    // construct formats, including Bit positions
+    let c = ArrayBigFixed::<2>::construct(0, &[0, 3, 0], Index::Bit(-1)).unwrap();
+    assert_eq!(BigFixed::from(c), BigFixed::construct(0, vec![0, 3, 0], Index::Bit(-1)).unwrap(), "construct");
+
+    let mut seed = 5;
+    for _ in 0..200 {
+        let x = random(&mut seed);
+        let a = ArrayBigFixed::<8>::try_from(&x).unwrap();
+        assert_eq!(BigFixed::from(&a), x, "round trip {}", x);
+        for p in -4..6 {
+            assert_eq!(a[p], x[p], "index {} of {}", p, x);
+            assert_eq!(a[Index::Bit(p * 5)], x[Index::Bit(p * 5)], "bit {} of {}", p * 5, x);
+        }
+    }
+}
+
+#[test]
+fn indexing() {
+    let mut a = ArrayBigFixed::<3>::try_from(BigFixed::from(5)).unwrap();
+    a[1] = 7;
+    a[Index::Bit(-1)] = GREATESTBIT;
+    assert_eq!(a.len, 3, "extended");
+    assert_eq!(BigFixed::from(a), BigFixed::try_from(7.0 * 65536.0 + 5.5).unwrap(), "written");
+    assert!(a.ensure_valid_position(Index::Position(-2)).is_err(), "out of capacity");
+}
+
+#[test]
+fn arithmetic() {
+    let mut seed = 17;
+    for _ in 0..500 {
+        let x = random(&mut seed);
+        let y = random(&mut seed);
+        let (a, b) = (ArrayBigFixed::<6>::try_from(&x).unwrap(), ArrayBigFixed::<6>::try_from(&y).unwrap());
+        // either the result matches BigFixed or it really does not fit
+        let check = |result: Result<(), BigFixedError>, array: ArrayBigFixed<6>, expected: BigFixed, message: &str| match result {
+            Ok(()) => assert_eq!(BigFixed::from(array), expected, "{}", message),
+            Err(e) => {
+                assert_eq!(e, BigFixedArrayError(ArrayError::CapacityExceeded), "{}", message);
+                assert!(expected.body.len() > 6, "{} fits", message);
+            }
+        };
+        let mut sum = a;
+        check(sum.add_assign(&b), sum, x.clone() + y.clone(), &format!("{} + {}", x, y));
+        let mut difference = a;
+        check(difference.sub_assign(&b), difference, x.clone() - y.clone(), &format!("{} - {}", x, y));
+        let mut negated = a;
+        check(negated.negate(), negated, (-x.clone()).unwrap(), &format!("-{}", x));
+        let mut product = a;
+        check(product.mul_assign(&b), product, x.clone() * y.clone(), &format!("{} * {}", x, y));
+    }
+    // carries past capacity fail, carries which format away do not
+    let max = ArrayBigFixed::<1>::try_from(BigFixed::from(0xffff)).unwrap();
+    let one = ArrayBigFixed::<1>::try_from(BigFixed::from(1)).unwrap();
+    let mut sum = max;
+    sum.add_assign(&one).unwrap();
+    assert_eq!(BigFixed::from(sum), BigFixed::from(0x10000), "carry into an empty body");
+    let mut sum = max;
+    assert!(sum.add_assign(&max).is_err(), "0x1fffe needs two digits");
+    let mut product = ArrayBigFixed::<1>::try_from(BigFixed::from(-65536)).unwrap();
+    product.mul_assign(&ArrayBigFixed::<1>::try_from(BigFixed::from(-65536)).unwrap()).unwrap();
+    assert_eq!(BigFixed::from(product), BigFixed::from(1i64 << 32), "head only product");
+}
+
+#[test]
+fn distant_positions() {
+    // nothing walks the gap between far apart values digit by digit
+    let far = 1isize << 36;
+    let high = ArrayBigFixed::<4>::construct(0, &[1], Index::Position(far)).unwrap();
+    let mut negated = high;
+    negated.negate().unwrap();
+    assert_eq!(BigFixed::from(negated), (-BigFixed::from(&high)).unwrap(), "negate");
+    let low = ArrayBigFixed::<4>::construct(ALLONES, &[5], Index::Position(-far)).unwrap();
+    for (x, y) in [(high, low), (low, high), (negated, low), (low, negated), (negated, high)] {
+        let (bx, by) = (BigFixed::from(&x), BigFixed::from(&y));
+        let mut sum = x;
+        match sum.add_assign(&y) {
+            Ok(()) => assert_eq!(BigFixed::from(sum), bx.clone() + by.clone(), "{:?} + {:?}", x, y),
+            Err(e) => assert_eq!(e, BigFixedArrayError(ArrayError::CapacityExceeded), "{:?} + {:?}", x, y)
+        }
+        let mut difference = x;
+        match difference.sub_assign(&y) {
+            Ok(()) => assert_eq!(BigFixed::from(difference), bx.clone() - by.clone(), "{:?} - {:?}", x, y),
+            Err(e) => assert_eq!(e, BigFixedArrayError(ArrayError::CapacityExceeded), "{:?} - {:?}", x, y)
+        }
+        assert_eq!(x == y, bx == by, "{:?} == {:?}", x, y);
+    }
+    // the head carries all the way through: -2^(16 * far) + 2^(16 * far) is zero
+    let mut zero = negated;
+    zero.add_assign(&high).unwrap();
+    assert!(zero.is_zero(), "cancel");
+    assert_eq!(zero, ArrayBigFixed::ZERO, "cancel equality");
+    // a small negative plus a far positive has a run of ones between them
+    let minus_one = ArrayBigFixed::<4>::try_from(BigFixed::from(-1)).unwrap();
+    let mut sum = high;
+    assert_eq!(sum.add_assign(&minus_one), Err(BigFixedArrayError(ArrayError::CapacityExceeded)), "2^(16 * far) - 1");
+    assert!(high != negated, "signs differ");
+}
@@ -1,4 +1,12 @@
-use bigfixed::{digit::*, index::*, cutoff::*, BigFixed};
+use bigfixed::{digit::*, index::*, cutoff::*, BigFixed, Body, INLINE_DIGITS};
+
+// == compares values, these tests are about representations
+macro_rules! assert_same {
+    ($x: expr, $y: expr, $($message: tt)*) => {
+        let (x, y) = (&$x, &$y);
+        assert!(x.structurally_eq(y), "{:?} is not {:?}: {}", x, y, format!($($message)*));
+    };
+}
 
 fn check<E>(x: Result<bool, E>, expected: bool, message: &str) {
     match x {
@@ -12,92 +20,92 @@ fn check<E>(x: Result<bool, E>, expected: bool, message: &str) {
 fn fix_position() {
     let mut zero_bit = BigFixed {
         head: 0,
-        body: vec![],
+        body: vec![].into(),
         position: Bit(0)
     };
     check(zero_bit.fix_position(), true, "fixing position");
     let zero_pos = BigFixed {
         head: 0,
-        body: vec![],
+        body: vec![].into(),
         position: Position(0)
     };
     check(zero_bit.full_eq(&zero_pos), true, "zero [0] vs zero (0)");
     let mut zero_bit2 = BigFixed {
         head: 0,
-        body: vec![],
+        body: vec![].into(),
         position: Bit(1)
     };
     check(zero_bit2.fix_position(), true, "fixing position");
     check(zero_bit2.full_eq(&zero_pos), true, "zero [1] vs zero (0)");
     let mut one_bit = BigFixed {
         head: 0,
-        body: vec![1],
+        body: vec![1].into(),
         position: Bit(0)
     };
     check(one_bit.fix_position(), true, "fixing position");
     let one_pos = BigFixed {
         head: 0,
-        body: vec![1],
+        body: vec![1].into(),
         position: Position(0)
     };
     check(one_bit.full_eq(&one_pos), true, "one [0] vs one (0)");
     let mut two_bit = BigFixed {
         head: 0,
-        body: vec![1],
+        body: vec![1].into(),
         position: Bit(1)
     };
     check(two_bit.fix_position(), true, "fixing position");
     let two_pos = BigFixed {
         head: 0,
-        body: vec![2],
+        body: vec![2].into(),
         position: Position(0)
     };
     check(two_bit.full_eq(&two_pos), true, "one [1] vs two (0)");
     let mut ten_bit = BigFixed {
         head: 0,
-        body: vec![1],
+        body: vec![1].into(),
         position: Bit(DIGITBITS as isize)
     };
     check(ten_bit.fix_position(), true, "fixing position");
     let ten_pos = BigFixed {
         head: 0,
-        body: vec![1],
+        body: vec![1].into(),
         position: Position(1)
     };
     check(ten_bit.full_eq(&ten_pos), true, "one [DIGITBITS] vs one (1)");
     let mut neg_one_bit = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Bit(0)
     };
     check(neg_one_bit.fix_position(), true, "fixing position");
     let neg_one_pos = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Position(0)
     };
     check(neg_one_bit.full_eq(&neg_one_pos), true, "neg one [0] vs neg one (0)");
     let mut neg_three_bit = BigFixed {
         head: ALLONES,
-        body: vec![3],
+        body: vec![3].into(),
         position: Bit(-1)
     };
     check(neg_three_bit.fix_position(), true, "fixing position");
     let neg_three_pos = BigFixed {
         head: ALLONES,
-        body: vec![GREATESTBIT, GREATESTBIT | 1],
+        body: vec![GREATESTBIT, GREATESTBIT | 1].into(),
         position: Position(-1)
     };
     check(neg_three_bit.full_eq(&neg_three_pos), true, "negative test");
     let mut neg_two_bit = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Bit(1)
     };
     check(neg_two_bit.fix_position(), true, "fixing position");
     let neg_two_pos = BigFixed {
         head: ALLONES,
-        body: vec![ALLONES << 1],
+        body: vec![ALLONES << 1].into(),
         position: Position(0)
     };
     check(neg_two_bit.full_eq(&neg_two_pos), true, "negative empty body");
@@ -107,24 +115,24 @@ fn fix_position() {
 fn format() {
     let real_zero = BigFixed {
         head: 0,
-        body: vec![],
+        body: vec![].into(),
         position: Position(0)
     };
-    assert_eq!(BigFixed::construct(0, vec![], Position(0)).unwrap(), real_zero, "zero zero");
-    assert_eq!(BigFixed::construct(0, vec![0,0,0,0,0,0,0,0,0,0], Bit(-140)).unwrap(), real_zero, "zero zeroes");
+    assert_same!(BigFixed::construct(0, vec![], Position(0)).unwrap(), real_zero, "zero zero");
+    assert_same!(BigFixed::construct(0, vec![0,0,0,0,0,0,0,0,0,0], Bit(-140)).unwrap(), real_zero, "zero zeroes");
     let real_neg_one = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Position(0)
     };
-    assert_eq!(BigFixed::construct(ALLONES, vec![], Bit(0)).unwrap(), real_neg_one, "neg one empty");
-    assert_eq!(BigFixed::construct(ALLONES, vec![ALLONES, ALLONES], Position(0)).unwrap(), real_neg_one, "neg one heads");
+    assert_same!(BigFixed::construct(ALLONES, vec![], Bit(0)).unwrap(), real_neg_one, "neg one empty");
+    assert_same!(BigFixed::construct(ALLONES, vec![ALLONES, ALLONES], Position(0)).unwrap(), real_neg_one, "neg one heads");
     let real_t1 = BigFixed {
         head: ALLONES,
-        body: vec![5],
+        body: vec![5].into(),
         position: Position(1)
     };
-    assert_eq!(BigFixed::construct(ALLONES, vec![0,0,5,ALLONES], Bit(DIGITBITS as isize * -1)).unwrap(), real_t1, "negative with body both sides");
+    assert_same!(BigFixed::construct(ALLONES, vec![0,0,5,ALLONES], Bit(-(DIGITBITS as isize))).unwrap(), real_t1, "negative with body both sides");
 }
 
 // format_c
@@ -133,57 +141,57 @@ fn format() {
 fn ensure_valid_range() {
     let padded = BigFixed {
         head: ALLONES,
-        body: vec![0, 0, 6, ALLONES, ALLONES],
+        body: vec![0, 0, 6, ALLONES, ALLONES].into(),
         position: Position(-6)
     };
     let mut no_growth = padded.clone();
     check(no_growth.ensure_valid_range(Position(0), Position(0)), false, "ensuring valid range");
-    assert_eq!(padded, no_growth, "no growth internal");
+    assert_same!(padded, no_growth, "no growth internal");
     check(no_growth.ensure_valid_range(no_growth.position, (no_growth.position + 1isize).unwrap()), false, "ensuring valid range");
-    assert_eq!(padded, no_growth, "no growth start");
+    assert_same!(padded, no_growth, "no growth start");
     check(no_growth.ensure_valid_range((no_growth.body_high().unwrap() - 1isize).unwrap(), no_growth.body_high().unwrap()), false, "ensuring valid range");
-    assert_eq!(padded, no_growth, "no growth end");
+    assert_same!(padded, no_growth, "no growth end");
     check(no_growth.ensure_valid_range(no_growth.position, no_growth.body_high().unwrap()), false, "ensuring valid range");
-    assert_eq!(padded, no_growth, "no growth full");
+    assert_same!(padded, no_growth, "no growth full");
     let mut growth = BigFixed {
         head: ALLONES,
-        body: vec![6],
+        body: vec![6].into(),
         position: Position(-4)
     };
     check(growth.ensure_valid_range(Bit(-5*(DIGITBITS as isize) - 1), Position(-3)), true, "ensuring valid range");
-    assert_eq!(growth, BigFixed {
+    assert_same!(growth, BigFixed {
         head: ALLONES,
-        body: vec![0, 0, 6],
+        body: vec![0, 0, 6].into(),
         position: Position(-6)
     }, "bit growth below");
     check(growth.ensure_valid_range(Bit(-3*(DIGITBITS as isize) - 3), Position(-2)), true, "ensuring valid range");
-    assert_eq!(growth, BigFixed {
+    assert_same!(growth, BigFixed {
         head: ALLONES,
-        body: vec![0, 0, 6, ALLONES],
+        body: vec![0, 0, 6, ALLONES].into(),
         position: Position(-6)
     }, "bit growth above");
     check(growth.ensure_valid_range(Position(-6), Position(-1)), true, "ensuring valid range");
-    assert_eq!(growth, padded, "position growth both ends");
+    assert_same!(growth, padded, "position growth both ends");
     let mut x = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Position(1)
     };
     check(x.ensure_valid_position(Position(-1)), true, "ensuring valid range");
-    assert_eq!(x, BigFixed {
+    assert_same!(x, BigFixed {
         head: ALLONES,
-        body: vec![0, 0],
+        body: vec![0, 0].into(),
         position: Position(-1)
     }, "negative no body to body below");
     x = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Position(-1)
     };
     check(x.ensure_valid_position(Position(1)), true, "ensuring valid position");
-    assert_eq!(x, BigFixed {
+    assert_same!(x, BigFixed {
         head: ALLONES,
-        body: vec![ALLONES, ALLONES, ALLONES],
+        body: vec![ALLONES, ALLONES, ALLONES].into(),
         position: Position(-1)
     }, "negative no body to body above");
 }
@@ -193,67 +201,67 @@ fn ensure_valid_range() {
 
 #[test]
 fn shift() {
-    assert_eq!(
+    assert_same!(
         BigFixed {
             head: 0,
-            body: vec![],
+            body: vec![].into(),
             position: Bit(100)
         }.shift(Bit(0)).unwrap(),
         BigFixed {
             head: 0,
-            body: vec![],
+            body: vec![].into(),
             position: Position(0)
         },
         "zero shift"
     );
-    assert_eq!(
+    assert_same!(
         BigFixed {
             head: 0,
-            body: vec![2],
+            body: vec![2].into(),
             position: Position(0)
         }.shift(Bit(-1)).unwrap(),
         BigFixed {
             head: 0,
-            body: vec![1],
+            body: vec![1].into(),
             position: Position(0)
         },
         "2 >> 1 == 1"
     );
-    assert_eq!(
+    assert_same!(
         BigFixed {
             head: 0,
-            body: vec![1],
+            body: vec![1].into(),
             position: Position(0)
         }.shift(Bit(1)).unwrap(),
         BigFixed {
             head: 0,
-            body: vec![2],
+            body: vec![2].into(),
             position: Position(0)
         },
         "1 << 1 == 2"
     );
-    assert_eq!(
+    assert_same!(
         BigFixed {
             head: ALLONES,
-            body: vec![],
+            body: vec![].into(),
             position: Position(0)
         }.shift(Position(100)).unwrap(),
         BigFixed {
             head: ALLONES,
-            body: vec![],
+            body: vec![].into(),
             position: Position(100)
         },
         "-1 shifting left 100 positions"
     );
-    assert_eq!(
+    assert_same!(
         BigFixed {
             head: ALLONES,
-            body: vec![1, ALLONES, 0, 3],
+            body: vec![1, ALLONES, 0, 3].into(),
             position: Bit(4)
         }.shift(Bit(-2)).unwrap(),
         BigFixed {
             head: ALLONES,
-            body: vec![1 << 2, ALLONES << 2, 3, 3 << 2, ALLONES << 2],
+            body: vec![1 << 2, ALLONES << 2, 3, 3 << 2, ALLONES << 2].into(),
             position: Position(0)
         },
         "negative multinumber 1"
@@ -273,11 +281,11 @@ fn cutoff() {
                 floating: None,
                 round: Rounding::$round
             }).unwrap();
-            assert_eq!(
+            assert_same!(
                 src,
                 BigFixed {
                     head: $head,
-                    body: $body,
+                    body: $body.into(),
                     position: Position($position)
                 },
                 $msg
@@ -292,11 +300,11 @@ fn cutoff() {
                 floating: Some(Index::Bit($bit)),
                 round: Rounding::$round
             }).unwrap();
-            assert_eq!(
+            assert_same!(
                 src,
                 BigFixed {
                     head: $head,
-                    body: $body,
+                    body: $body.into(),
                     position: Position($position)
                 },
                 $msg
@@ -310,11 +318,11 @@ fn cutoff() {
                 floating: None,
                 round: Rounding::$round
             }).unwrap();
-            assert_eq!(
+            assert_same!(
                 src,
                 BigFixed {
                     head: $head,
-                    body: $body,
+                    body: $body.into(),
                     position: Position($position)
                 },
                 $msg
@@ -328,11 +336,11 @@ fn cutoff() {
                 floating: Some(Index::Position($pos)),
                 round: Rounding::$round
             }).unwrap();
-            assert_eq!(
+            assert_same!(
                 src,
                 BigFixed {
                     head: $head,
-                    body: $body,
+                    body: $body.into(),
                     position: Position($position)
                 },
                 $msg
@@ -379,7 +387,7 @@ fn cutoff() {
     test_cutoff_fixed_position!(-1, -6, Floor, ALLONES, vec![], 0, "-1 (-6) .");
     test_cutoff_floating_position!(-1, -6, Floor, ALLONES, vec![], 0, "-1 . (-6)");
 
-    let two_nums = u128::from(&BigFixed::construct(0, vec![127, 127], Position(0)).unwrap());
+    let two_nums = BigFixed::construct(0, vec![127, 127], Position(0)).unwrap().to_u128_wrapping();
     test_cutoff_fixed_bit!(two_nums, 0, Floor, 0, vec![127, 127], 0, "11 [0] . f");
     test_cutoff_fixed_bit!(two_nums, 0, Ceiling, 0, vec![127, 127], 0, "11 [0] . c");
     test_cutoff_fixed_bit!(two_nums, 0, Round, 0, vec![127, 127], 0, "11 [0] . r");
@@ -416,4 +424,157 @@ fn cutoff() {
     test_cutoff_floating_position!(two_nums, -5, Floor, 0, vec![64], 1, "11 . (-5) f");
     test_cutoff_floating_position!(two_nums, -5, Ceiling, 0, vec![128], 1, "11 . (-5) c");
     test_cutoff_floating_position!(two_nums, -5, Round, 0, vec![128], 1, "11 . (-5) r");
+
+    // TowardsZero and AwayFromZero go by the sign. They used to be swapped for negative values, giving -12 and -8 for -11 here.
+    test_cutoff_fixed_bit!(-11, 2, TowardsZero, ALLONES, vec![0xfff8], 0, "-11 [2] t");
+    test_cutoff_fixed_bit!(-11, 2, AwayFromZero, ALLONES, vec![0xfff4], 0, "-11 [2] a");
+    test_cutoff_fixed_bit!(11, 2, TowardsZero, 0, vec![8], 0, "11 [2] t");
+    test_cutoff_fixed_bit!(11, 2, AwayFromZero, 0, vec![12], 0, "11 [2] a");
+}
+
+#[test]
+fn inline_body() {
+    const ZERO: BigFixed = BigFixed::ZERO;
+    assert!(ZERO.is_zero() && !ZERO.body.spilled(), "const zero");
+    for x in [BigFixed::from(1), BigFixed::from(-12345678), BigFixed::from(u64::MAX), BigFixed::from(i128::MIN), BigFixed::from(u128::MAX)] {
+        assert!(!x.body.spilled(), "native integer {} is inline", x);
+    }
+    assert_eq!(Body::new().inline_size(), INLINE_DIGITS, "inline size");
+
+    // growing past the inline capacity spills and everything keeps working
+    let mut x = BigFixed::from(5);
+    x[Position(INLINE_DIGITS as isize + 2)] = 7;
+    assert!(x.body.spilled(), "spilled");
+    assert_eq!(x[Position(INLINE_DIGITS as isize + 2)], 7, "index after spill");
+    x.ensure_valid_range(Position(-3), Position(0)).unwrap();
+    assert_eq!(x.range_iter(Position(-3), Position(2)).unwrap().collect::<Vec<Digit>>(), vec![0, 0, 0, 5, 0], "range_iter");
+    x.format().unwrap();
+    let mut y = BigFixed::from(-1);
+    y.overwrite(&x);
+    assert_same!(y, x, "overwrite");
+    y.overwrite(&BigFixed::from(3));
+    assert_same!(y, BigFixed::from(3), "overwrite smaller");
+}
+
+#[test]
+fn ordering_and_hashing() {
+    use std::collections::{BTreeMap, HashMap, hash_map::DefaultHasher};
+    use std::hash::{Hash, Hasher};
+    let hash = |x: &BigFixed| {
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    };
+    // the same numbers with extra zero limbs below, extra head limbs above and a Bit position
+    let pairs = [
+        (BigFixed::from(5), BigFixed {head: 0, body: vec![0, 0, 5, 0].into(), position: Index::Position(-2)}),
+        (BigFixed::from(-65536), BigFixed {head: ALLONES, body: vec![0, 0, ALLONES].into(), position: Index::Position(-1)}),
+        (BigFixed::from(0), BigFixed {head: 0, body: vec![0, 0].into(), position: Index::Position(3)}),
+        (BigFixed::from(3), BigFixed {head: 0, body: vec![3 << 4].into(), position: Index::Bit(-4)})
+    ];
+    for (x, y) in pairs.iter() {
+        assert_eq!(x, y, "{} ==", x);
+        assert!(!x.structurally_eq(y), "{} structurally", x);
+        assert_eq!(x.cmp(y), std::cmp::Ordering::Equal, "{} cmp", x);
+        assert_eq!(hash(x), hash(y), "{} hash", x);
+    }
+    let values: Vec<BigFixed> = [-3.5, -1.0, -0.25, 0.0, 0.125, 1.0, 70000.0].iter().map(|&x| BigFixed::try_from(x).unwrap()).collect();
+    for (i, x) in values.iter().enumerate() {
+        for (j, y) in values.iter().enumerate() {
+            assert_eq!(x.cmp(y), i.cmp(&j), "{} cmp {}", x, y);
+        }
+    }
+    let mut tree = BTreeMap::new();
+    let mut table = HashMap::new();
+    for (i, (x, y)) in pairs.iter().enumerate() {
+        tree.insert(x.clone(), i);
+        table.insert(x.clone(), i);
+        assert_eq!(tree.get(y), Some(&i), "tree {}", y);
+        assert_eq!(table.get(y), Some(&i), "table {}", y);
+    }
+    assert_eq!(tree.keys().next(), Some(&BigFixed::from(-65536)), "least key");
+    let (low, high) = (BigFixed::from(-1), BigFixed::from(2));
+    assert_eq!(BigFixed::from(7).clamp(low.clone(), high.clone()), high, "clamp above");
+    assert_eq!(BigFixed::from(-7).clamp(low.clone(), high.clone()), low, "clamp below");
+    assert_eq!(low.clone().max(high.clone()), high, "max");
+    assert_eq!(low.clone().min(high), low, "min");
+}
+
+#[test]
+fn hash_padding() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |x: &BigFixed| {
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    };
+    // the same value padded with zeros below and head digits above, as a Bit position, and shifted by a few bits within a Bit position
+    let variants = |x: &BigFixed| {
+        let p = x.position.value();
+        let mut padded = vec![0, 0];
+        padded.extend(x.body.iter().copied());
+        padded.extend([x.head, x.head]);
+        let mut variants = vec![BigFixed {head: x.head, body: padded.into(), position: Index::Position(p - 2)}];
+        // positions too far out have no Bit equivalent
+        let bit = match p.checked_mul(DIGITBITS as isize) {
+            Some(bit) => bit,
+            None => return variants
+        };
+        variants.push(BigFixed {head: x.head, body: x.body.clone(), position: Index::Bit(bit)});
+        for k in [1, 5, DIGITBITS - 1] {
+            let mut body = vec![];
+            let mut carry = 0;
+            for &d in x.body.iter().chain([x.head].iter()) {
+                body.push((((d as DoubleDigit) << k) | carry) as Digit);
+                carry = (d as DoubleDigit) >> (DIGITBITS - k);
+            }
+            variants.push(BigFixed {head: x.head, body: body.into(), position: Index::Bit(bit - k as isize)});
+        }
+        variants
+    };
+    let mut values: Vec<BigFixed> = [0.0, 1.0, -1.0, 5.5, -65536.0, 1e-9, -3.0e12].iter().map(|&x| BigFixed::try_from(x).unwrap()).collect();
+    values.push(BigFixed {head: 0, body: vec![7].into(), position: Index::Position(isize::MAX - 1)});
+    values.push(BigFixed {head: ALLONES, body: vec![3].into(), position: Index::Position(isize::MIN / 32)});
+    for x in values.iter() {
+        for y in variants(x) {
+            assert_eq!(&y, x, "{:?} == {}", y, x);
+            assert!(!y.structurally_eq(x), "{:?} structurally {}", y, x);
+            assert_eq!(hash(&y), hash(x), "{:?} hash {}", y, x);
+        }
+    }
+    assert_ne!(hash(&BigFixed::from(1)), hash(&BigFixed::from(2)), "different values");
+    assert_ne!(hash(&BigFixed::from(1)), hash(&BigFixed::from(65536)), "different positions");
+}
+
+#[test]
+fn ordering_extremes() {
+    use std::cmp::Ordering::*;
+    // positions at the ends of isize, where index arithmetic overflows, and values far apart
+    let huge = BigFixed {head: 0, body: vec![1].into(), position: Index::Position(isize::MAX)};
+    let tiny = BigFixed {head: 0, body: vec![1].into(), position: Index::Position(isize::MIN)};
+    let negative_tiny = BigFixed {head: ALLONES, body: vec![ALLONES].into(), position: Index::Position(isize::MIN)};
+    let bit = BigFixed {head: 0, body: vec![1].into(), position: Index::Bit(isize::MAX)};
+    let one = BigFixed::from(1);
+    assert_eq!(huge.cmp(&one), Greater, "huge");
+    assert_eq!(tiny.cmp(&one), Less, "tiny");
+    assert_eq!(tiny.cmp(&BigFixed::from(0)), Greater, "tiny positive");
+    assert_eq!(negative_tiny.cmp(&BigFixed::from(0)), Less, "tiny negative");
+    assert_eq!(huge.cmp(&tiny), Greater, "far apart");
+    assert_eq!(bit.cmp(&huge), Less, "bit below position");
+    assert_eq!(huge.cmp(&huge.clone()), Equal, "huge equal");
+    assert_eq!(BigFixed {head: 0, body: vec![0, 2].into(), position: Index::Position(isize::MIN)}.cmp(&BigFixed {head: 0, body: vec![1 << 1].into(), position: Index::Position(isize::MIN + 1)}), Equal, "padded at the bottom");
+    assert_eq!(BigFixed {head: ALLONES, body: vec![5, ALLONES, ALLONES].into(), position: Index::Position(isize::MAX - 3)}.cmp(&BigFixed {head: ALLONES, body: vec![5].into(), position: Index::Position(isize::MAX - 3)}), Equal, "padded at the top");
+    // agrees with the sign of the difference
+    let mut seed: u64 = 13;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        BigFixed::from((seed >> 8) as i64 >> (seed % 56)).shift(Index::Bit((seed % 64) as isize - 32)).unwrap()
+    };
+    for _ in 0..300 {
+        let (x, y) = (next(), next());
+        let d = x.clone() - y.clone();
+        let expected = if d.is_zero() {Equal} else if d.is_neg() {Less} else {Greater};
+        assert_eq!(x.cmp(&y), expected, "{} cmp {}", x, y);
+    }
 }
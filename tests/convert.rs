@@ -1,4 +1,6 @@
-use bigfixed::{digit::*, Index, BigFixed};
+use bigfixed::{digit::*, Index, Cutoff, CutoffScheme, Rounding, BigFixed, Body, BigFixedError, BigFixedParseError, BigFixedFloatError, BigFixedIntError, ParseError, FloatError, IntError, RadixDigits, BigFixedBytesError, BytesError};
+
+use bigfixed::schemes::SCHEME_F64;
 
 use std::{iter};
 
@@ -13,7 +15,7 @@ fn test(template: &BigFixed, tester: &BigFixed, msg: &str) {
 fn from_ints() {
     let zero = BigFixed {
         head: 0,
-        body: vec![],
+        body: vec![].into(),
         position: Index::Position(0)
     };
     test(&zero, &BigFixed::from(0u8), "u8");
@@ -29,7 +31,7 @@ fn from_ints() {
     
     let one = BigFixed {
         head: 0,
-        body: vec![1],
+        body: vec![1].into(),
         position: Index::Position(0)
     };
     test(&one, &BigFixed::from(1u8), "u8");
@@ -45,7 +47,7 @@ fn from_ints() {
     
     let neg_one = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Index::Position(0)
     };
     test(&neg_one, &BigFixed::from(-1i8), "i8");
@@ -56,7 +58,7 @@ fn from_ints() {
     
     let lots_of_ones = BigFixed {
         head: 0,
-        body: iter::repeat(ALLONES).take(128 / DIGITBITS).collect(),
+        body: iter::repeat_n(ALLONES, 128 / DIGITBITS).collect(),
         position: Index::Position(0)
     };
     test(&lots_of_ones, &BigFixed::from(-1i128 as u128), "-1u128");
@@ -66,23 +68,62 @@ fn from_ints() {
 fn to_ints() {
     // signed ints are waiting on ops...
     let zero = BigFixed::from(0u8);
-    assert_eq!(0u8, u8::from(&zero), "u8");
+    assert_eq!(0u8, zero.to_u8_wrapping(), "u8");
     //assert_eq!(0i8, i8::from(&zero), "i8");
-    assert_eq!(0u16, u16::from(&zero), "u16");
+    assert_eq!(0u16, zero.to_u16_wrapping(), "u16");
     //assert_eq!(0i16, i16::from(&zero), "i16");
-    assert_eq!(0u32, u32::from(&zero), "u32");
+    assert_eq!(0u32, zero.to_u32_wrapping(), "u32");
     //assert_eq!(0i32, i32::from(&zero), "i32");
-    assert_eq!(0u64, u64::from(&zero), "u64");
+    assert_eq!(0u64, zero.to_u64_wrapping(), "u64");
     //assert_eq!(0i64, i64::from(&zero), "i64");
-    assert_eq!(0u128, u128::from(&zero), "u128");
+    assert_eq!(0u128, zero.to_u128_wrapping(), "u128");
     //assert_eq!(0i128, i128::from(&zero), "i128");
 
     let neg_one = BigFixed::from(-1i8);
-    assert_eq!(-1i8 as u8, u8::from(&neg_one), "-1u8");
-    assert_eq!(-1i16 as u16, u16::from(&neg_one), "-1u16");
-    assert_eq!(-1i32 as u32, u32::from(&neg_one), "-1u32");
-    assert_eq!(-1i64 as u64, u64::from(&neg_one), "-1u64");
-    assert_eq!(-1i128 as u128, u128::from(&neg_one), "-1u128");
+    assert_eq!(-1i8 as u8, neg_one.to_u8_wrapping(), "-1u8");
+    assert_eq!(-1i16 as u16, neg_one.to_u16_wrapping(), "-1u16");
+    assert_eq!(-1i32 as u32, neg_one.to_u32_wrapping(), "-1u32");
+    assert_eq!(-1i64 as u64, neg_one.to_u64_wrapping(), "-1u64");
+    assert_eq!(-1i128 as u128, neg_one.to_u128_wrapping(), "-1u128");
+}
+
+#[test]
+fn checked_ints() {
+    let x = BigFixed::from(1).shift(Index::Bit(40)).unwrap() + BigFixed::from(7);
+    let half = |n: i32| BigFixed::from(2*n + 1).shift(Index::Bit(-1)).unwrap(); // n + 0.5
+    assert_eq!(i32::try_from(&BigFixed::from(-5)), Ok(-5), "in range");
+    assert_eq!(u64::try_from(&x), Ok((1 << 40) + 7), "u64");
+    assert_eq!(u32::try_from(&x), Err(BigFixedIntError(IntError::Overflow)), "overflow");
+    assert_eq!(u8::try_from(&BigFixed::from(-1)), Err(BigFixedIntError(IntError::Overflow)), "negative unsigned");
+    assert_eq!(i16::try_from(&half(1)), Err(BigFixedIntError(IntError::Fractional)), "fractional");
+    assert_eq!(i128::try_from(&BigFixed::from(i128::MIN)), Ok(i128::MIN), "i128 min");
+    assert_eq!(u128::try_from(&BigFixed::from(u128::MAX)), Ok(u128::MAX), "u128 max");
+    assert_eq!(usize::try_from(&BigFixed::from(usize::MAX)), Ok(usize::MAX), "usize max");
+    assert_eq!(i8::try_from(&BigFixed::from(128)), Err(BigFixedIntError(IntError::Overflow)), "i8 max + 1");
+    // unformatted values give the same answers
+    let unformatted = BigFixed::construct(0, vec![0, 3, 0], Index::Position(-1)).unwrap();
+    assert_eq!(u8::try_from(&unformatted), Ok(3), "unformatted");
+
+    // wrapping keeps the low bits of the floor
+    assert_eq!(x.to_u32_wrapping(), 7, "wrapping");
+    assert_eq!(BigFixed::from(-1).to_u8_wrapping(), 255, "wrapping negative");
+    assert_eq!(BigFixed::from(200).to_i8_wrapping(), -56, "wrapping signed");
+    assert_eq!(half(2).to_u16_wrapping(), 2, "wrapping floors");
+    assert_eq!(half(-3).to_i64_wrapping(), -3, "wrapping floors negative");
+    // saturating clamps the floor
+    assert_eq!(x.to_u32_saturating(), u32::MAX, "saturating");
+    assert_eq!(BigFixed::from(-1).to_u8_saturating(), 0, "saturating negative");
+    assert_eq!(BigFixed::from(-1i64 << 40).to_i32_saturating(), i32::MIN, "saturating min");
+    assert_eq!(half(-3).to_isize_saturating(), -3, "saturating floors");
+    assert_eq!(half(255).to_u8_saturating(), 255, "saturating fraction");
+    // rounded
+    assert_eq!(half(2).to_i32_rounded(Rounding::Round), Ok(3), "round");
+    assert_eq!(half(-3).to_i32_rounded(Rounding::Round), Ok(-2), "round half up");
+    assert_eq!(half(2).to_i32_rounded(Rounding::TowardsZero), Ok(2), "towards zero");
+    assert_eq!(half(-3).to_i32_rounded(Rounding::AwayFromZero), Ok(-3), "away from zero");
+    assert_eq!(half(-3).to_i32_rounded(Rounding::Ceiling), Ok(-2), "ceiling");
+    assert_eq!(half(255).to_u8_rounded(Rounding::Round), Err(BigFixedIntError(IntError::Overflow)), "rounded overflow");
+    assert_eq!(half(255).to_u8_rounded(Rounding::Floor), Ok(255), "rounded floor");
 }
 
 #[test]
@@ -90,13 +131,580 @@ fn floats() {
     for a in [0f32, 1f32, 10f32] {
         for b in [1f32, 2f32, 100001f32] {
             let c = a / b;
-            assert_eq!(c, f32::from(BigFixed::from(c)), "f32 {}", c);
+            assert_eq!(c, f32::from(BigFixed::try_from(c).unwrap()), "f32 {}", c);
         }
     }
     for a in [0f64, 1f64, 10f64] {
         for b in [1f64, 2f64, 100001f64] {
             let c = a / b;
-            assert_eq!(c, f64::from(BigFixed::from(c)), "f64 {}", c);
+            assert_eq!(c, f64::from(BigFixed::try_from(c).unwrap()), "f64 {}", c);
+        }
+    }
+}
+
+#[test]
+fn parse() {
+    let half = BigFixed::from(1).shift(Index::Bit(-1)).unwrap();
+    assert_eq!("0.5".parse::<BigFixed>().unwrap(), half, "0.5");
+    assert_eq!(".5".parse::<BigFixed>().unwrap(), half, ".5");
+    assert_eq!("5e-1".parse::<BigFixed>().unwrap(), half, "5e-1");
+    assert_eq!("-12.75".parse::<BigFixed>().unwrap(), BigFixed::from(-51).shift(Index::Bit(-2)).unwrap(), "-12.75");
+    assert_eq!("+1200".parse::<BigFixed>().unwrap(), BigFixed::from(1200), "+1200");
+    assert_eq!("12E2".parse::<BigFixed>().unwrap(), BigFixed::from(1200), "12E2");
+    assert_eq!("-0.000".parse::<BigFixed>().unwrap(), BigFixed::ZERO, "-0");
+    assert_eq!(
+        "340282366920938463463374607431768211455".parse::<BigFixed>().unwrap(),
+        BigFixed::from(u128::MAX),
+        "u128::MAX"
+    );
+    // exactly representable values ignore the lack of a cutoff
+    assert_eq!(BigFixed::parse_c("0.0625", Cutoff {fixed: None, floating: None, round: Rounding::Floor}).unwrap(), BigFixed::from(1).shift(Index::Bit(-4)).unwrap(), "0.0625");
+
+    let float = |bits: isize| Cutoff {
+        fixed: None,
+        floating: Some(Index::Bit(bits)),
+        round: Rounding::Round
+    };
+    for s in ["0.1", "-0.1", "3.14159", "1e-10", "-2.5e3", "123456789.987654321", "6.02214076e23"] {
+        assert_eq!(BigFixed::parse_c(s, float(23)).unwrap(), BigFixed::try_from(s.parse::<f32>().unwrap()).unwrap(), "f32 {}", s);
+    }
+    for s in ["0.1", "-0.1", "3.14159", "1e-10", "-2.5e3", "123456789.987654321", "6.02214076e23", "1.7976931348623157e308", "2.2250738585072014e-308"] {
+        assert_eq!(BigFixed::parse_c(s, float(52)).unwrap(), BigFixed::try_from(s.parse::<f64>().unwrap()).unwrap(), "f64 {}", s);
+    }
+
+    let fixed = |round: Rounding| Cutoff {
+        fixed: Some(Index::Bit(-4)),
+        floating: None,
+        round
+    };
+    let sixteenths = |x: i32| BigFixed::from(x).shift(Index::Bit(-4)).unwrap();
+    assert_eq!(BigFixed::parse_c("0.1", fixed(Rounding::Floor)).unwrap(), sixteenths(1), "0.1 floor");
+    assert_eq!(BigFixed::parse_c("0.1", fixed(Rounding::Ceiling)).unwrap(), sixteenths(2), "0.1 ceiling");
+    assert_eq!(BigFixed::parse_c("0.1", fixed(Rounding::Round)).unwrap(), sixteenths(2), "0.1 round");
+    assert_eq!(BigFixed::parse_c("-0.1", fixed(Rounding::Floor)).unwrap(), sixteenths(-2), "-0.1 floor");
+    assert_eq!(BigFixed::parse_c("-0.1", fixed(Rounding::Ceiling)).unwrap(), sixteenths(-1), "-0.1 ceiling");
+    assert_eq!(BigFixed::parse_c("0.01", fixed(Rounding::Ceiling)).unwrap(), sixteenths(1), "0.01 ceiling");
+    assert_eq!(BigFixed::parse_c("0.01", fixed(Rounding::Floor)).unwrap(), sixteenths(0), "0.01 floor");
+
+    assert_eq!(BigFixed::parse_c("0.1", Cutoff {fixed: None, floating: None, round: Rounding::Floor}), Err(BigFixedParseError(ParseError::NonTerminating)), "0.1 without cutoff");
+    for (s, e) in [("", ParseError::Empty), ("-", ParseError::InvalidDigit), (".", ParseError::InvalidDigit), ("1.2.3", ParseError::InvalidDigit), ("abc", ParseError::InvalidDigit), ("1e", ParseError::InvalidExponent), ("1e+", ParseError::InvalidExponent), ("1e1.5", ParseError::InvalidExponent), (" 1", ParseError::InvalidDigit)] {
+        assert_eq!(s.parse::<BigFixed>(), Err(BigFixedParseError(e)), "{:?}", s);
+    }
+
+    // exponents too large to build fail up front whatever the cutoff
+    let integer = Cutoff::INTEGER;
+    for s in ["1e10000000", "1e999999999999", "-7e-10000000", "1e-999999999999"] {
+        assert_eq!(s.parse::<BigFixed>(), Err(BigFixedParseError(ParseError::TooLarge)), "{}", s);
+        assert_eq!(BigFixed::parse_c(s, integer), Err(BigFixedParseError(ParseError::TooLarge)), "{} fixed", s);
+    }
+    assert_eq!("1e60000".parse::<BigFixed>().unwrap().decimal_exponent(), Ok(Some(60000)), "1e60000");
+    assert!(BigFixed::parse_c("1e-20000", integer).unwrap().is_zero(), "1e-20000 fixed");
+    assert_eq!("0x1p999999999999".parse::<BigFixed>().unwrap(), BigFixed::from(1).shift(Index::Bit(999999999999)).unwrap(), "binary exponents are free");
+}
+
+#[test]
+fn radix_literals() {
+    let x = "-0x1a.8p+4".parse::<BigFixed>().unwrap();
+    assert_eq!(x, BigFixed::from(-424), "-0x1a.8p+4");
+    assert_eq!(format!("{:x}", x), "-1a8", "-424 hex");
+    let y = BigFixed::from(-53).shift(Index::Bit(-1)).unwrap();
+    assert_eq!("-0x1A.8".parse::<BigFixed>().unwrap(), y, "-0x1A.8");
+    assert_eq!(format!("{:#x}", y), "-0x1a.8", "-26.5 hex");
+    assert_eq!(format!("{:X}", y), "-1A.8", "-26.5 upper hex");
+    assert_eq!(format!("{:o}", y), "-32.4", "-26.5 octal");
+    assert_eq!(format!("{:#b}", y), "-0b11010.1", "-26.5 binary");
+    assert_eq!(format!("{:b}", BigFixed::from(1).shift(Index::Bit(-2)).unwrap()), "0.01", "0.25 binary");
+    assert_eq!(format!("{:b}", BigFixed::ZERO), "0", "0 binary");
+    let unformatted = BigFixed {head: 0, body: Body::from_slice(&[0, 0, 0x8000, 1]), position: Index::Position(-3)};
+    assert_eq!(format!("{:x}", unformatted), "1.8", "low zero digits");
+    let negative = BigFixed {head: ALLONES, body: Body::from_slice(&[0, 0, 0x8000, 0xfffe]), position: Index::Position(-3)};
+    assert_eq!(format!("{:b}", negative), "-1.1", "negative low zero digits");
+    assert_eq!(format!("{:#010x}", BigFixed::from(26)), "0x0000001a", "padded hex");
+    assert_eq!(format!("{:+o}", BigFixed::from(8)), "+10", "signed octal");
+
+    assert_eq!("0b0.0001".parse::<BigFixed>().unwrap(), BigFixed::from(1).shift(Index::Bit(-4)).unwrap(), "0b0.0001");
+    assert_eq!("0o.1p-3".parse::<BigFixed>().unwrap(), BigFixed::from(1).shift(Index::Bit(-6)).unwrap(), "0o.1p-3");
+    assert_eq!("0x1p100".parse::<BigFixed>().unwrap(), BigFixed::from(1).shift(Index::Bit(100)).unwrap(), "0x1p100");
+    assert_eq!("0x1e".parse::<BigFixed>().unwrap(), BigFixed::from(30), "0x1e");
+    assert_eq!("0b102".parse::<BigFixed>(), Err(BigFixedParseError(ParseError::InvalidDigit)), "0b102");
+    assert_eq!("0x1p".parse::<BigFixed>(), Err(BigFixedParseError(ParseError::InvalidExponent)), "0x1p");
+    assert_eq!("0x".parse::<BigFixed>(), Err(BigFixedParseError(ParseError::InvalidDigit)), "0x");
+
+    // round trip through every power of two radix
+    for x in [
+        BigFixed::construct(0, vec![3, 0, ALLONES, 77], Index::Bit(-37)).unwrap(),
+        BigFixed::construct(ALLONES, vec![5, 1 << 9], Index::Bit(-3)).unwrap(),
+        BigFixed::from(u128::MAX).shift(Index::Bit(-200)).unwrap()
+    ] {
+        for s in [format!("{:#b}", x), format!("{:#o}", x), format!("{:#x}", x), format!("{:#X}", x)] {
+            assert_eq!(s.parse::<BigFixed>().unwrap(), x, "{}", s);
         }
     }
 }
+
+#[test]
+fn display() {
+    let x = BigFixed::from(-53).shift(Index::Bit(-3)).unwrap(); // -6.625
+    assert_eq!(format!("{}", x), "-6.625", "exact");
+    assert_eq!(format!("{:.5}", x), "-6.62500", "padded places");
+    assert_eq!(format!("{:.2}", x), "-6.62", "round half up");
+    assert_eq!(format!("{:.0}", x), "-7", "no places");
+    assert_eq!(format!("{:.2}", x.display_rounded(Rounding::Floor)), "-6.63", "floor");
+    assert_eq!(format!("{:.2}", x.display_rounded(Rounding::Ceiling)), "-6.62", "ceiling");
+    assert_eq!(format!("{:.2}", x.display_rounded(Rounding::AwayFromZero)), "-6.63", "away from zero");
+    assert_eq!(format!("{:.1}", x.display_rounded(Rounding::TowardsZero)), "-6.6", "towards zero");
+    assert_eq!(format!("{:>10.1}", x), "      -6.6", "right aligned");
+    assert_eq!(format!("{:*<8.1}", x), "-6.6****", "fill");
+    assert_eq!(format!("{:08.1}", x), "-00006.6", "zero padded");
+    assert_eq!(format!("{:+}", BigFixed::from(1200)), "+1200", "plus");
+    assert_eq!(format!("{}", BigFixed::ZERO), "0", "zero");
+    assert_eq!(format!("{:.3}", BigFixed::ZERO), "0.000", "zero places");
+    assert_eq!(format!("{:.1}", BigFixed::from(-1).shift(Index::Bit(-5)).unwrap()), "0.0", "rounds to zero");
+    assert_eq!(format!("{}", BigFixed::from(1).shift(Index::Bit(-10)).unwrap()), "0.0009765625", "small");
+    assert_eq!(format!("{:.3}", BigFixed::from(1).shift(Index::Bit(-1000)).unwrap()), "0.000", "tiny");
+    assert_eq!(format!("{:.2}", BigFixed::from(1).shift(Index::Bit(-1000)).unwrap().display_rounded(Rounding::Ceiling)), "0.01", "tiny ceiling");
+    assert_eq!(format!("{}", BigFixed::from(u128::MAX)), u128::MAX.to_string(), "u128::MAX");
+    assert_eq!(format!("{}", BigFixed::from(i128::MIN)), i128::MIN.to_string(), "i128::MIN");
+    assert_eq!(format!("{:.3}", BigFixed::try_from(0.1f64).unwrap()), "0.100", "0.1");
+    assert_eq!(format!("{:.20}", BigFixed::try_from(0.1f64).unwrap()), format!("{:.20}", 0.1f64), "0.1 to 20 places");
+
+    let scheme = CutoffScheme {
+        arithmetic: Cutoff {
+            fixed: Some(Index::Bit(-8)),
+            floating: None,
+            round: Rounding::Floor
+        },
+        comparisons: Cutoff {
+            fixed: Some(Index::Bit(-2)),
+            floating: None,
+            round: Rounding::Ceiling
+        }
+    };
+    let y = scheme.claim(BigFixed::from(13).shift(Index::Bit(-4)).unwrap()).unwrap(); // 0.8125
+    assert_eq!(format!("{}", y), "1", "comparison cutoff");
+    assert_eq!(format!("{:.1}", y.value), "0.8", "bare value");
+    assert_eq!(format!("{:.1}", y), "0.9", "one ceiling rounding");
+
+    // a precision rounds once from the stored value, not again after the comparison cutoff
+    let quarters = CutoffScheme {
+        arithmetic: scheme.arithmetic,
+        comparisons: Cutoff {
+            fixed: Some(Index::Bit(-2)),
+            floating: None,
+            round: Rounding::Round
+        }
+    };
+    let z = quarters.claim(BigFixed::from(15).shift(Index::Bit(-6)).unwrap()).unwrap(); // 0.234375
+    assert_eq!(format!("{:.1}", z), "0.2", "single rounding");
+    assert_eq!(format!("{:.1e}", z), "2.3e-1", "single rounding scientific");
+    assert_eq!(format!("{:e}", z), "2.5e-1", "comparison cutoff without a precision");
+
+    // without a precision a bound value shows the shortest decimal equal to it under comparisons
+    let tenth = SCHEME_F64.claim(BigFixed::try_from(0.1f64).unwrap()).unwrap();
+    assert_eq!(format!("{}", tenth), "0.1", "0.1 under the f64 scheme");
+    for f in [-0.3, 1.0 / 3.0, 123.456, 2.5, 1e-300, 6.02214076e23] {
+        let bound = SCHEME_F64.claim(BigFixed::try_from(f).unwrap()).unwrap();
+        let shown = format!("{}", bound);
+        assert_eq!(shown, bound.value.to_shortest_decimal(SCHEME_F64.comparisons).unwrap(), "{} shortest", f);
+        assert_eq!(bound, SCHEME_F64.claim(BigFixed::parse_c(&shown, SCHEME_F64.comparisons).unwrap()).unwrap(), "{} round trip", f);
+    }
+    assert_eq!(format!("{:>6}", tenth), "   0.1", "padded shortest");
+    assert_eq!(format!("{:+}", tenth), "+0.1", "signed shortest");
+}
+
+#[test]
+fn scientific() {
+    let x = BigFixed::from(24691).shift(Index::Bit(-1)).unwrap(); // 12345.5
+    assert_eq!(format!("{:e}", x), "1.23455e4", "exact");
+    assert_eq!(format!("{:E}", x), "1.23455E4", "upper");
+    assert_eq!(format!("{:.2e}", x), "1.23e4", "rounded");
+    assert_eq!(format!("{:.7e}", x), "1.2345500e4", "padded");
+    assert_eq!(format!("{:.0e}", x), "1e4", "no digits");
+    assert_eq!(format!("{:.2e}", x.display_rounded(Rounding::Ceiling)), "1.24e4", "ceiling");
+    assert_eq!(format!("{:e}", BigFixed::from(1200)), "1.2e3", "trailing zeros");
+    assert_eq!(format!("{:e}", BigFixed::from(-1)), "-1e0", "-1");
+    assert_eq!(format!("{:e}", BigFixed::ZERO), "0e0", "zero");
+    assert_eq!(format!("{:.2e}", BigFixed::ZERO), "0.00e0", "zero digits");
+    assert_eq!(format!("{:.2e}", "999.6".parse::<BigFixed>().unwrap()), "1.00e3", "carry");
+    assert_eq!(format!("{:+12.1e}", BigFixed::from(-53).shift(Index::Bit(-3)).unwrap()), "      -6.6e0", "width");
+    assert_eq!(format!("{:e}", BigFixed::from(1).shift(Index::Bit(-3)).unwrap()), "1.25e-1", "eighth");
+    for f in [0.1f64, -0.1f64, 2f64.powi(-1000), 2f64.powi(1000), 123456.789, -9.999e-200, 1e300, f64::MIN_POSITIVE] {
+        for digits in [0, 1, 4, 16, 30] {
+            assert_eq!(format!("{:.*e}", digits, BigFixed::try_from(f).unwrap()), format!("{:.*e}", digits, f), "{} to {}", f, digits);
+        }
+    }
+    let exact = format!("{:e}", BigFixed::try_from(2f64.powi(-20)).unwrap());
+    assert_eq!(exact, format!("{:e}", 2f64.powi(-20)), "2^-20");
+    assert_eq!(BigFixed::ZERO.decimal_exponent(), Ok(None), "zero has no decimal exponent");
+    assert_eq!(x.decimal_exponent(), Ok(Some(4)), "12345.5");
+    assert_eq!(BigFixed::try_from(-0.001).unwrap().decimal_exponent(), Ok(Some(-3)), "-0.001");
+
+    let scheme = CutoffScheme {
+        arithmetic: Cutoff {
+            fixed: None,
+            floating: Some(Index::Bit(64)),
+            round: Rounding::Floor
+        },
+        comparisons: Cutoff {
+            fixed: None,
+            floating: Some(Index::Bit(3)),
+            round: Rounding::Round
+        }
+    };
+    let y = scheme.claim(BigFixed::from(13).shift(Index::Bit(-400)).unwrap()).unwrap();
+    assert_eq!(format!("{:.3e}", y), format!("{:.3e}", 13f64 * 2f64.powi(-400)), "comparison cutoff");
+}
+
+#[test]
+fn shortest_decimal() {
+    let f64_cutoff = Cutoff {
+        fixed: None,
+        floating: Some(Index::Bit(52)),
+        round: Rounding::Round
+    };
+    assert_eq!(BigFixed::try_from(0.1f64).unwrap().to_shortest_decimal(f64_cutoff).unwrap(), "0.1", "0.1");
+    for f in [0.1f64, -0.3, 1.0 / 3.0, 123.456, 1e20, 1200.0, 2f64.powi(-20), 0.000001, -98765.4321, 2.5] {
+        assert_eq!(BigFixed::try_from(f).unwrap().to_shortest_decimal(f64_cutoff).unwrap(), format!("{}", f), "{}", f);
+    }
+    for f in [1e21, 1e-300, -1.7976931348623157e308, 6.02214076e23] {
+        let s = BigFixed::try_from(f).unwrap().to_shortest_decimal(f64_cutoff).unwrap();
+        assert_eq!(s.parse::<f64>().unwrap(), f, "{}", s);
+        assert_eq!(s, format!("{:e}", f), "{}", f);
+    }
+    assert_eq!(BigFixed::ZERO.to_shortest_decimal(f64_cutoff).unwrap(), "0", "zero");
+
+    // directed rounding only accepts decimals on one side
+    let floor = Cutoff {
+        fixed: Some(Index::Bit(-4)),
+        floating: None,
+        round: Rounding::Floor
+    };
+    let x = BigFixed::from(3).shift(Index::Bit(-4)).unwrap(); // 0.1875
+    assert_eq!(x.to_shortest_decimal(floor).unwrap(), "0.2", "floor");
+    let ceiling = Cutoff {round: Rounding::Ceiling, ..floor};
+    assert_eq!(x.to_shortest_decimal(ceiling).unwrap(), "0.18", "ceiling");
+    for s in ["0.2", "0.18"] {
+        let c = if s == "0.2" {floor} else {ceiling};
+        assert_eq!(BigFixed::parse_c(s, c).unwrap(), x, "{}", s);
+    }
+    // no cutoff means the exact expansion
+    let none = Cutoff {fixed: None, floating: None, round: Rounding::Round};
+    assert_eq!(x.to_shortest_decimal(none).unwrap(), "0.1875", "exact");
+
+    // long values: the result round trips and one digit fewer never does
+    let mut seed: u64 = 5;
+    let body: Vec<Digit> = (0..188).map(|_| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 40) as Digit
+    }).collect();
+    let long = BigFixed::construct(0, body, Index::Position(-100)).unwrap();
+    let wide = Cutoff {fixed: None, floating: Some(Index::Bit(2990)), round: Rounding::Round};
+    let mut target = long.clone();
+    target.cutoff(wide).unwrap();
+    let s = long.to_shortest_decimal(wide).unwrap();
+    assert_eq!(BigFixed::parse_c(&s, wide).unwrap(), target, "long round trip");
+    let digits = s.split('e').next().unwrap().chars().filter(|c| c.is_ascii_digit()).collect::<String>().trim_start_matches('0').len();
+    for round in [Rounding::Round, Rounding::Floor, Rounding::Ceiling] {
+        let (significand, exponent, _) = target.to_scientific(Some(digits - 2), round).unwrap();
+        let shorter = BigFixed::parse_c(&format!("{}e{}", significand, exponent), wide).unwrap();
+        assert!(shorter != target, "{} digits round trip", digits - 1);
+    }
+}
+
+#[test]
+fn radix() {
+    let x = BigFixed::parse_c("-1234.5625", Cutoff {fixed: None, floating: None, round: Rounding::Round}).unwrap();
+    let digits = x.to_radix(10, 10, Rounding::Round).unwrap();
+    assert_eq!(digits, RadixDigits::from_u8(10, true, &[5, 2, 6, 5, 4, 3, 2, 1], 4), "exact decimal");
+    assert_eq!(digits.digits_u8().unwrap(), vec![5, 2, 6, 5, 4, 3, 2, 1], "bytes");
+    assert_eq!(BigFixed::from_radix(&digits).unwrap(), x, "decimal round trip");
+    assert_eq!(BigFixed::from_str_radix("-1234.5625", 10), Ok(x.clone()), "from_str_radix");
+    assert_eq!(BigFixed::from_str_radix("+z.i", 36), Ok(BigFixed::try_from(35.5).unwrap()), "radix 36");
+    assert_eq!(BigFixed::from_str_radix(".", 10), Err(BigFixedParseError(ParseError::Empty)), "empty");
+    assert_eq!(BigFixed::from_str_radix("12", 2), Err(BigFixedParseError(ParseError::InvalidDigit)), "invalid digit");
+    assert_eq!(BigFixed::from_radix(&RadixDigits::from_u8(10, false, &[1], -1000000)), Err(BigFixedParseError(ParseError::TooLarge)), "huge exponent");
+    assert_eq!(BigFixed::from_radix(&RadixDigits::from_u8(16, false, &[1], -1000000)).unwrap(), BigFixed::from(1).shift(Index::Bit(4000000)).unwrap(), "huge power of two");
+    assert_eq!(BigFixed::from_radix(&RadixDigits::from_u8(2, false, &[1], isize::MIN)), Err(BigFixedParseError(ParseError::TooLarge)), "point overflow");
+    assert_eq!(BigFixed::from_radix(&RadixDigits::from_u8(1, false, &[0], 0)), Err(BigFixedParseError(ParseError::InvalidRadix)), "radix 1");
+    assert_eq!(x.to_radix(0, 3, Rounding::Round), Err(BigFixedParseError(ParseError::InvalidRadix)), "radix 0");
+    assert_eq!(x.to_radix(10, 2, Rounding::Round).unwrap(), RadixDigits::from_u8(10, true, &[6, 5, 4, 3, 2, 1], 2), "rounded");
+    assert_eq!(x.to_radix(10, 1, Rounding::Floor).unwrap(), RadixDigits::from_u8(10, true, &[6, 4, 3, 2, 1], 1), "floor");
+    assert_eq!(x.to_radix(10, 0, Rounding::TowardsZero).unwrap(), RadixDigits::from_u8(10, true, &[4, 3, 2, 1], 0), "towards zero");
+    assert_eq!(BigFixed::from(1200).to_radix(10, 3, Rounding::Round).unwrap(), RadixDigits::from_u8(10, false, &[0, 0, 2, 1], 0), "integer");
+    assert_eq!(BigFixed::ZERO.to_radix(7, 3, Rounding::Round).unwrap(), RadixDigits::from_u8(7, false, &[], 0), "zero");
+    let small = BigFixed::from(3).shift(Index::Bit(-7)).unwrap(); // 0.0234375
+    assert_eq!(small.to_radix(10, 3, Rounding::Round).unwrap(), RadixDigits::from_u8(10, false, &[3, 2], 3), "leading zeros");
+
+    // odd radixes never terminate for fractions
+    let half = BigFixed::from(1).shift(Index::Bit(-1)).unwrap();
+    let thirds = half.to_radix(3, 4, Rounding::Floor).unwrap();
+    assert_eq!(thirds, RadixDigits::from_u8(3, false, &[1, 1, 1, 1], 4), "base 3");
+    assert_eq!(half.to_radix(3, 4, Rounding::Round).unwrap().digits, vec![2, 1, 1, 1], "base 3 ties round up");
+    assert_eq!(BigFixed::from_radix(&thirds), Err(BigFixedParseError(ParseError::NonTerminating)), "non terminating");
+    let cutoff = Cutoff {fixed: Some(Index::Bit(-20)), floating: None, round: Rounding::Round};
+    // within half a unit of 2^-20 from 40/81
+    let err = (BigFixed::from_radix_c(&thirds, cutoff).unwrap() * BigFixed::from(81) - BigFixed::from(40)).abs().unwrap();
+    assert!(err.shift(Index::Bit(21)).unwrap() <= BigFixed::from(81), "40/81");
+    assert_eq!(
+        BigFixed::from_radix(&RadixDigits::from_u8(3, false, &[3], 0)),
+        Err(BigFixedParseError(ParseError::InvalidDigit)),
+        "invalid digit"
+    );
+
+    // large values go through the divide and conquer path
+    let mut seed: u64 = 12345;
+    let mut body = vec![];
+    for _ in 0..300 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        body.push((seed >> 40) as Digit);
+    }
+    let big = BigFixed::construct(ALLONES, body, Index::Position(-100)).unwrap();
+    for radix in [2, 3, 10, 16, 1000, 65537, u32::MAX] {
+        // odd radixes need enough digits to resolve 2^-1600
+        let frac_digits = if radix % 2 == 0 {usize::MAX} else {1600 / (31 - radix.leading_zeros() as usize) + 2};
+        let digits = big.to_radix(radix, frac_digits, Rounding::Floor).unwrap();
+        assert!(digits.digits.iter().all(|&d| d < radix), "digit range {}", radix);
+        let back = BigFixed::from_radix_c(&digits, Cutoff {fixed: Some(Index::Position(-100)), floating: None, round: Rounding::Round}).unwrap();
+        if radix % 2 == 0 {
+            assert_eq!(BigFixed::from_radix(&digits).unwrap(), big, "exact round trip {}", radix);
+        }
+        assert_eq!(back, big, "round trip {}", radix);
+    }
+    let hex: String = big.to_radix(16, usize::MAX, Rounding::Floor).unwrap().digits.iter().rev()
+        .map(|&d| char::from_digit(d, 16).unwrap()).collect();
+    let formatted = format!("{:x}", big.abs().unwrap());
+    assert_eq!(hex, formatted.trim_start_matches("0x").replace('.', ""), "hex digits");
+}
+
+#[test]
+fn float_formats() {
+    // every finite nonzero binary16 and bfloat16 pattern
+    for bits in 0..=u16::MAX {
+        if bits & 0x7c00 != 0 && bits & 0x7c00 != 0x7c00 {
+            let x = BigFixed::from_f16_bits(bits).unwrap();
+            assert_eq!(x.to_f16_bits().unwrap(), bits, "f16 {:x}", bits);
+        }
+        if bits & 0x7f80 != 0 && bits & 0x7f80 != 0x7f80 {
+            let x = BigFixed::from_bf16_bits(bits).unwrap();
+            assert_eq!(x.to_bf16_bits().unwrap(), bits, "bf16 {:x}", bits);
+            assert_eq!(x, BigFixed::try_from(f32::from_bits((bits as u32) << 16)).unwrap(), "bf16 as f32 {:x}", bits);
+        }
+    }
+    assert_eq!(BigFixed::from_f16_bits(0x3c00).unwrap(), BigFixed::from(1), "f16 one");
+    assert_eq!(BigFixed::from_f16_bits(0xc000).unwrap(), BigFixed::from(-2), "f16 minus two");
+    assert_eq!(BigFixed::from_f16_bits(0x7bff).unwrap(), BigFixed::from(65504), "f16 max");
+    assert_eq!(BigFixed::try_from(0.1f32).unwrap().to_bf16_bits().unwrap(), 0x3dcd, "bf16 rounds to nearest");
+    // an exponent equal to the bias with a zero significand is a power of two, not zero
+    assert_eq!(BigFixed::try_from(2f32.powi(127)).unwrap(), BigFixed::from(1).shift(Index::Bit(127)).unwrap(), "2^127");
+
+    let f128_one: u128 = 0x3fff << 112;
+    assert_eq!(BigFixed::from_f128_bits(f128_one).unwrap(), BigFixed::from(1), "f128 one");
+    let x87_one = [0, 0, 0, 0, 0, 0, 0, 0x80, 0xff, 0x3f];
+    assert_eq!(BigFixed::from_x87_bits(x87_one).unwrap(), BigFixed::from(1), "x87 one");
+    assert_eq!(BigFixed::from(1).to_x87_bits().unwrap(), x87_one, "x87 one bits");
+    // the explicit integer bit: pseudo-denormals have the denormal exponent 1 - bias, and unnormals, pseudo-infinities and pseudo-NaNs are invalid
+    let x87_min_normal = [0, 0, 0, 0, 0, 0, 0, 0x80, 0x01, 0x00];
+    let pseudo_denormal = [1, 0, 0, 0, 0, 0, 0, 0x80, 0x00, 0x80];
+    let expected = (-(BigFixed::from(1).shift(Index::Bit(-16382)).unwrap() + BigFixed::from(1).shift(Index::Bit(-16382 - 63)).unwrap())).unwrap();
+    assert_eq!(BigFixed::from_x87_bits(x87_min_normal).unwrap(), BigFixed::from(1).shift(Index::Bit(-16382)).unwrap(), "x87 smallest normal");
+    assert_eq!(BigFixed::from_x87_bits(pseudo_denormal).unwrap(), expected, "x87 pseudo-denormal");
+    assert_eq!(BigFixed::from_x87_bits([0, 0, 0, 0, 0, 0, 0, 0x80, 0x00, 0x00]).unwrap(), BigFixed::from_x87_bits(x87_min_normal).unwrap(), "x87 pseudo-denormal is the smallest normal");
+    assert_eq!(expected.to_x87_bits().unwrap(), [1, 0, 0, 0, 0, 0, 0, 0x80, 0x01, 0x80], "x87 pseudo-denormal encodes as a normal");
+    assert_eq!(BigFixed::from_x87_bits([0, 0, 0, 0, 0, 0, 0, 0x40, 0x00, 0x00]).unwrap(), BigFixed::from(1).shift(Index::Bit(-16383)).unwrap(), "x87 denormal");
+    assert_eq!(BigFixed::from_x87_bits([0, 0, 0, 0, 0, 0, 0, 0x40, 0xff, 0x3f]), Err(BigFixedFloatError(FloatError::NaN)), "x87 unnormal");
+    assert_eq!(BigFixed::from_x87_bits([0, 0, 0, 0, 0, 0, 0, 0x00, 0xff, 0x7f]), Err(BigFixedFloatError(FloatError::NaN)), "x87 pseudo-infinity");
+    assert_eq!(BigFixed::from_x87_bits([1, 0, 0, 0, 0, 0, 0, 0x00, 0xff, 0x7f]), Err(BigFixedFloatError(FloatError::NaN)), "x87 pseudo-NaN");
+    assert_eq!(BigFixed::from_x87_bits([0, 0, 0, 0, 0, 0, 0, 0x80, 0xff, 0xff]), Err(BigFixedFloatError(FloatError::NegativeInfinity)), "x87 negative infinity");
+    for f in [0.1f64, -0.3, 1.0 / 3.0, 123.456, 1e300, -1e-300, 2.5, f64::MAX, f64::MIN_POSITIVE] {
+        let x = BigFixed::try_from(f).unwrap();
+        let wide = x.to_f128_bits().unwrap();
+        assert_eq!(BigFixed::from_f128_bits(wide).unwrap(), x, "f128 {}", f);
+        assert_eq!(wide >> 127, (f < 0.0) as u128, "f128 sign {}", f);
+        let extended = x.to_x87_bits().unwrap();
+        assert_eq!(BigFixed::from_x87_bits(extended).unwrap(), x, "x87 {}", f);
+        assert_eq!(extended[7] & 0x80, 0x80, "x87 integer bit {}", f);
+    }
+    // more precision than f64 survives the wider formats
+    let third = BigFixed::parse_c("0.33333333333333333333333333333333333333", Cutoff {
+        fixed: None,
+        floating: Some(Index::Bit(112)),
+        round: Rounding::Floor
+    }).unwrap();
+    assert_eq!(BigFixed::from_f128_bits(third.to_f128_bits().unwrap()).unwrap(), third, "f128 third");
+}
+
+#[test]
+fn float_specials() {
+    assert_eq!(BigFixed::try_from(f64::NAN), Err(BigFixedFloatError(FloatError::NaN)), "nan");
+    assert_eq!(BigFixed::try_from(f64::INFINITY), Err(BigFixedFloatError(FloatError::Infinity)), "infinity");
+    assert_eq!(BigFixed::try_from(f32::NEG_INFINITY), Err(BigFixedFloatError(FloatError::NegativeInfinity)), "negative infinity");
+    assert_eq!(BigFixed::try_from(-0f64).unwrap(), BigFixed::ZERO, "negative zero");
+    // subnormals are exact
+    let least = BigFixed::from(1).shift(Index::Bit(-1074)).unwrap();
+    assert_eq!(BigFixed::try_from(5e-324f64).unwrap(), least, "least subnormal");
+    assert_eq!(BigFixed::try_from(f64::MIN_POSITIVE / 4.0).unwrap(), BigFixed::from(1).shift(Index::Bit(-1024)).unwrap(), "subnormal");
+    assert_eq!(f64::from(&least), 5e-324, "least subnormal back");
+    // every binary16 pattern but NaNs and negative zero round trips, including subnormals and infinities
+    for bits in 0..=u16::MAX {
+        let x = BigFixed::from_f16_bits(bits);
+        if bits & 0x7c00 == 0x7c00 {
+            assert!(x.is_err(), "f16 special {:x}", bits);
+        } else if bits != 0x8000 {
+            assert_eq!(x.unwrap().to_f16_bits().unwrap(), bits, "f16 {:x}", bits);
+        }
+    }
+
+    let f16 = |x: &BigFixed, round: Rounding| x.clone().float_to_bits(5, 15, 10, round).unwrap().to_u16_wrapping();
+    // overflow goes to infinity unless rounding towards zero
+    let big = BigFixed::from(65520);
+    assert_eq!(f16(&big, Rounding::Round), 0x7c00, "overflow round");
+    assert_eq!(f16(&big, Rounding::Floor), 0x7bff, "overflow floor");
+    let big = BigFixed::from(-65520);
+    assert_eq!(f16(&big, Rounding::Floor), 0xfc00, "negative overflow floor");
+    assert_eq!(f16(&big, Rounding::TowardsZero), 0xfbff, "negative overflow towards zero");
+    assert_eq!(f16(&BigFixed::from(65519), Rounding::Round), 0x7bff, "below overflow");
+    // gradual underflow
+    let tiny = BigFixed::from(1).shift(Index::Bit(-25)).unwrap();
+    assert_eq!(f16(&tiny, Rounding::Round), 0x0001, "half the least subnormal");
+    assert_eq!(f16(&tiny, Rounding::Floor), 0x0000, "underflow floor");
+    let tiny = BigFixed::from(-1).shift(Index::Bit(-26)).unwrap();
+    assert_eq!(f16(&tiny, Rounding::Round), 0x8000, "signed zero");
+    assert_eq!(f16(&tiny, Rounding::Floor), 0x8001, "negative underflow floor");
+    assert_eq!(f16(&BigFixed::from(3).shift(Index::Bit(-24)).unwrap(), Rounding::TowardsZero), 0x0003, "subnormal");
+
+    // directed rounding brackets the value
+    let tenth = BigFixed::try_from(0.1f64).unwrap();
+    let low = f32::from_bits(tenth.clone().float_to_bits(8, 127, 23, Rounding::Floor).unwrap().to_u32_wrapping());
+    let high = f32::from_bits(tenth.clone().float_to_bits(8, 127, 23, Rounding::Ceiling).unwrap().to_u32_wrapping());
+    assert_eq!(high.to_bits(), low.to_bits() + 1, "bracket");
+    assert!((low as f64) < 0.1 && 0.1 < (high as f64), "bracket values");
+    // to nearest agrees with the native cast away from ties, across normals, subnormals and overflow
+    let mut seed: u64 = 987654321;
+    for _ in 0..2000 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let f = f64::from_bits(seed);
+        if f.is_finite() {
+            assert_eq!(f32::from(&BigFixed::try_from(f).unwrap()).to_bits(), (f as f32).to_bits(), "{:e}", f);
+            assert_eq!(f64::from(&BigFixed::try_from(f).unwrap()), f, "{:e}", f);
+        }
+    }
+    for f in [1e-40f64, 3e38, 4e38, -1e-46, 1.5e-45] {
+        assert_eq!(f32::from(&BigFixed::try_from(f).unwrap()).to_bits(), (f as f32).to_bits(), "{:e}", f);
+    }
+}
+
+#[test]
+fn ratio() {
+    let ratio = |x: &BigFixed| {
+        let (n, d) = x.to_ratio().unwrap();
+        (i128::try_from(&n).unwrap(), i128::try_from(&d).unwrap())
+    };
+    assert_eq!(ratio(&BigFixed::try_from(-1234.5625).unwrap()), (-19753, 16), "dyadic");
+    assert_eq!(ratio(&BigFixed::from(-96)), (-96, 1), "integer");
+    assert_eq!(ratio(&BigFixed::from(0)), (0, 1), "zero");
+
+    let bits = |b: isize, round: Rounding| Cutoff {
+        fixed: Some(Index::Bit(b)),
+        floating: None,
+        round
+    };
+    let unbounded = Cutoff {
+        fixed: None,
+        floating: None,
+        round: Rounding::Round
+    };
+    assert_eq!(BigFixed::from_ratio_c(&BigFixed::from(3), &BigFixed::from(4), unbounded).unwrap(), BigFixed::try_from(0.75).unwrap(), "exact");
+    assert_eq!(BigFixed::from_ratio_c(&BigFixed::from(-21), &BigFixed::from(-7), unbounded).unwrap(), BigFixed::from(3), "exact integer");
+    assert_eq!(BigFixed::from_ratio_c(&BigFixed::from(1), &BigFixed::from(3), unbounded), Err(BigFixedError::NonTerminating), "non terminating");
+    assert_eq!(BigFixed::from_ratio_c(&BigFixed::from(1), &BigFixed::from(0), unbounded), Err(BigFixedError::DivisionByZero), "division by zero");
+    assert!(BigFixed::from_ratio_c(&BigFixed::from(0), &BigFixed::from(0), bits(-4, Rounding::Floor)).is_err(), "zero by zero");
+    // 1/3 = 0.0101... and -1/3 at 4 bits
+    let third = |n: i32, round: Rounding| BigFixed::from_ratio_c(&BigFixed::from(n), &BigFixed::from(3), bits(-4, round)).unwrap();
+    assert_eq!(third(1, Rounding::Floor), BigFixed::try_from(0.3125).unwrap(), "floor");
+    assert_eq!(third(1, Rounding::Ceiling), BigFixed::try_from(0.375).unwrap(), "ceiling");
+    assert_eq!(third(1, Rounding::Round), BigFixed::try_from(0.3125).unwrap(), "round");
+    assert_eq!(third(-1, Rounding::TowardsZero), BigFixed::try_from(-0.3125).unwrap(), "towards zero");
+    assert_eq!(third(-1, Rounding::AwayFromZero), BigFixed::try_from(-0.375).unwrap(), "away from zero");
+    // agrees with division for a long quotient
+    let cutoff = bits(-200, Rounding::Round);
+    let num = BigFixed::try_from(std::f64::consts::E).unwrap();
+    let den = BigFixed::try_from(-0.7).unwrap();
+    let quotient = BigFixed::from_ratio_c(&num, &den, cutoff).unwrap();
+    let mut error = quotient.clone() * den.clone() - num.clone();
+    error.cutoff(Cutoff {
+        fixed: Some(Index::Bit(-199)),
+        floating: None,
+        round: Rounding::TowardsZero
+    }).unwrap();
+    assert!(error.is_zero(), "quotient error");
+
+    let best = |x: &BigFixed, max: i32| {
+        let (n, d) = x.best_rational_approximation(&BigFixed::from(max)).unwrap();
+        (i128::try_from(&n).unwrap(), i128::try_from(&d).unwrap())
+    };
+    let pi = BigFixed::try_from(std::f64::consts::PI).unwrap();
+    assert_eq!(best(&pi, 1000), (355, 113), "pi");
+    assert_eq!(best(&pi, 7), (22, 7), "pi small");
+    assert_eq!(best(&pi, 1), (3, 1), "pi integer");
+    assert_eq!(best(&BigFixed::try_from(-std::f64::consts::PI).unwrap(), 100), (-311, 99), "negative semiconvergent");
+    assert_eq!(best(&BigFixed::try_from(0.625).unwrap(), 100), (5, 8), "exact");
+    assert_eq!(best(&BigFixed::from(0), 5), (0, 1), "zero");
+    assert_eq!(pi.best_rational_approximation(&BigFixed::try_from(0.5).unwrap()), Err(BigFixedError::DivisionByZero), "bound below 1");
+    assert!(pi.best_rational_approximation(&BigFixed::from(-3)).is_err(), "negative bound");
+}
+
+#[test]
+fn bytes() {
+    let cases = [
+        (BigFixed::from(0), vec![0, 0, 0]),
+        (BigFixed::from(1), vec![0, 0, 1, 1]),
+        (BigFixed::from(-1), vec![1, 0, 0]),
+        (BigFixed::from(-256), vec![1, 2, 0]),
+        (BigFixed::from(0x1234), vec![0, 0, 2, 0x34, 0x12]),
+        (BigFixed::from(0x120000), vec![0, 4, 1, 0x12]),
+        (BigFixed::from(-0x1234), vec![1, 0, 2, 0xcc, 0xed]),
+        (BigFixed::try_from(0.5).unwrap(), vec![0, 1, 1, 0x80]),
+        (BigFixed::try_from(-2.75).unwrap(), vec![1, 1, 2, 0x40, 0xfd])
+    ];
+    for (x, encoded) in cases.iter() {
+        assert_eq!(&x.to_bytes().unwrap(), encoded, "encode {}", x);
+        assert_eq!(&BigFixed::from_bytes(encoded).unwrap(), x, "decode {}", x);
+    }
+    // round trips at every byte alignment and signs, which also exercises positions off Digit boundaries
+    let mut seed: u64 = 42;
+    for _ in 0..200 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let mut x = BigFixed::from(((seed >> 16) as i64) >> (seed % 48)).shift(Index::Bit((seed % 97) as isize - 60)).unwrap();
+        x.format().unwrap();
+        let encoded = x.to_bytes().unwrap();
+        assert_eq!(BigFixed::from_bytes(&encoded).unwrap(), x, "{}", x);
+        // unformatted inputs encode the same way
+        let mut padded = x.clone();
+        padded.ensure_valid_range(Index::Position(-3), Index::Position(5)).unwrap();
+        assert_eq!(padded.to_bytes().unwrap(), encoded, "padded {}", x);
+    }
+    // several values back to back
+    let mut stream = vec![];
+    BigFixed::from(7).write_bytes(&mut stream).unwrap();
+    BigFixed::from(-9).write_bytes(&mut stream).unwrap();
+    let mut input = stream.as_slice();
+    assert_eq!(BigFixed::read_bytes(&mut input).unwrap(), BigFixed::from(7), "first");
+    assert_eq!(BigFixed::read_bytes(&mut input).unwrap(), BigFixed::from(-9), "second");
+    assert!(input.is_empty(), "consumed");
+
+    let rejects: [(&[u8], BytesError); 10] = [
+        (&[], BytesError::Truncated),
+        (&[0, 0, 2, 1], BytesError::Truncated),
+        (&[0, 0x80], BytesError::Truncated),
+        (&[0, 0, 1, 1, 0], BytesError::TrailingBytes),
+        (&[2, 0, 0], BytesError::InvalidHead),
+        (&[0, 0x80, 0x00, 0], BytesError::InvalidVarint),
+        (&[0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f, 0], BytesError::InvalidVarint),
+        (&[0, 0, 2, 0, 1], BytesError::NonCanonical),
+        (&[1, 0, 1, 0xff], BytesError::NonCanonical),
+        (&[0, 2, 0], BytesError::NonCanonical)
+    ];
+    for (bytes, error) in rejects.iter() {
+        assert_eq!(BigFixed::from_bytes(bytes), Err(BigFixedBytesError(*error)), "{:?}", bytes);
+    }
+}
@@ -31,19 +31,19 @@ fn index_mut_position() {
     x[Index::Position(0)] = 0;
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![0],
+        body: vec![0].into(),
         position: Index::Position(0)
     }).unwrap(), "one to zero");
     x[Index::Position(3)] = 1;
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![0, 0, 0, 1],
+        body: vec![0, 0, 0, 1].into(),
         position: Index::Position(0)
     }).unwrap(), "into head");
     x[Index::Position(-2)] = ALLONES;
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![ALLONES, 0, 0, 0, 0, 1],
+        body: vec![ALLONES, 0, 0, 0, 0, 1].into(),
         position: Index::Position(-2)
     }).unwrap(), "into tail");
 }
@@ -57,19 +57,19 @@ fn index_mut_bit() {
     x[Index::Bit(0)] = 0;
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![0],
+        body: vec![0].into(),
         position: Index::Position(0)
     }).unwrap(), "one to zero");
     x[Index::Bit(3 * DIGITBITS as isize + 3)] = 1;
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![0, 0, 0, 1],
+        body: vec![0, 0, 0, 1].into(),
         position: Index::Position(0)
     }).unwrap(), "into head");
     x[Index::Bit(-2 * DIGITBITS as isize)] = ALLONES;
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![ALLONES, 0, 0, 0, 0, 1],
+        body: vec![ALLONES, 0, 0, 0, 0, 1].into(),
         position: Index::Position(-2)
     }).unwrap(), "into tail");
 }
@@ -80,19 +80,34 @@ fn set_bit() {
     x.set_bit(4, 0);
     assert!(x.full_eq(&BigFixed {
         head: ALLONES,
-        body: vec![!16],
+        body: vec![!16].into(),
         position: Index::Position(0)
     }).unwrap(), "one bit");
     x.set_bit(4, 1);
     assert!(x.full_eq(&BigFixed {
         head: ALLONES,
-        body: vec![ALLONES],
+        body: vec![ALLONES].into(),
         position: Index::Position(0)
     }).unwrap(), "one bit");
     x.set_bit(-1, 0);
     assert!(x.full_eq(&BigFixed {
         head: ALLONES,
-        body: vec![0, ALLONES],
+        body: vec![0, ALLONES].into(),
         position: Index::Position(-1)
     }).unwrap(), "one bit");
 }
+
+#[test]
+fn index_ordering() {
+    use Index::{Position, Bit};
+    assert_eq!(Position(1), Bit(DIGITBITS as isize), "same value");
+    assert_ne!(Position(0), Bit(5), "bit within a position");
+    assert!(Position(0) < Bit(5), "ordered by value");
+    assert!(Bit(-1) < Position(0), "negative bit");
+    // positions whose bit value overflows are beyond every bit
+    assert!(Position(isize::MAX) > Bit(isize::MAX), "huge position");
+    assert!(Position(isize::MIN) < Bit(isize::MIN), "tiny position");
+    assert_eq!(Position(isize::MAX).max(Bit(0)), Position(isize::MAX), "max does not panic");
+    assert_eq!(-Position(3), Ok(Position(-3)), "neg");
+    assert!(Position(isize::MIN).negate().is_err(), "negate overflow");
+}
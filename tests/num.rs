@@ -0,0 +1,70 @@
+#![cfg(feature = "num-traits")]
+
+use bigfixed::{Index, Cutoff, CutoffScheme, Rounding, BigFixed};
+
+use num_traits::{Zero, One, FromPrimitive, ToPrimitive, Pow, CheckedAdd, CheckedSub, CheckedMul, CheckedDiv};
+
+// generic code written against num-traits only
+fn sum_of_powers<T: Zero + Clone + CheckedAdd + Pow<u32, Output = T>>(xs: &[T], exp: u32) -> Option<T> {
+    xs.iter().try_fold(T::zero(), |total, x| total.checked_add(&x.clone().pow(exp)))
+}
+
+#[test]
+fn big_fixed() {
+    assert!(BigFixed::zero().is_zero(), "zero");
+    assert_eq!(BigFixed::one(), BigFixed::from(1), "one");
+    assert_eq!(BigFixed::from_i64(-5), Some(BigFixed::from(-5)), "from_i64");
+    assert_eq!(BigFixed::from_u128(u128::MAX), Some(BigFixed::from(u128::MAX)), "from_u128");
+    assert_eq!(BigFixed::from_f64(-2.75), Some(BigFixed::try_from(-2.75).unwrap()), "from_f64");
+    assert_eq!(BigFixed::from_f64(f64::NAN), None, "from NaN");
+    let x = BigFixed::try_from(-2.75).unwrap();
+    assert_eq!(x.to_i64(), Some(-2), "truncates");
+    assert_eq!(x.to_u64(), None, "negative to unsigned");
+    assert_eq!(BigFixed::from(u64::MAX).to_i64(), None, "out of range");
+    assert_eq!(x.to_f64(), Some(-2.75), "to_f64");
+    assert_eq!(x.clone().pow(3u32), BigFixed::try_from(-20.796875).unwrap(), "pow");
+    assert_eq!((&x).pow(0usize), BigFixed::from(1), "pow 0");
+    assert_eq!(BigFixed::from(3).pow(40u8), BigFixed::from(3i128.pow(40)), "pow 40");
+    assert_eq!(x.checked_sub(&BigFixed::from(1)), Some(BigFixed::try_from(-3.75).unwrap()), "checked_sub");
+    assert_eq!(x.checked_mul(&x), Some(BigFixed::try_from(7.5625).unwrap()), "checked_mul");
+    let xs: Vec<BigFixed> = [1, -2, 3].iter().map(|&n| BigFixed::from(n)).collect();
+    assert_eq!(sum_of_powers(&xs, 2), Some(BigFixed::from(14)), "generic");
+}
+
+#[test]
+fn truncation() {
+    // ToPrimitive truncates towards zero like the float impls
+    for (x, i) in [(-2.75, -2i64), (-0.5, 0), (-7.0625, -7), (-65536.99, -65536), (2.75, 2)] {
+        let big = BigFixed::try_from(x).unwrap();
+        assert_eq!(big.to_i64(), Some(i), "{}", x);
+        assert_eq!(big.to_i64(), x.to_i64(), "{} agrees with f64", x);
+        assert_eq!(big.to_i32(), x.to_i32(), "{} to_i32", x);
+        assert_eq!(big.to_u64(), x.to_u64(), "{} to_u64", x);
+    }
+    assert_eq!(BigFixed::try_from(-0.5).unwrap().to_u8(), Some(0), "-0.5 truncates to 0");
+    assert_eq!(BigFixed::try_from(-1.5).unwrap().to_u8(), None, "-1.5 truncates to -1");
+    assert_eq!(BigFixed::try_from(-128.75).unwrap().to_i8(), Some(-128), "truncates into range");
+    assert_eq!(BigFixed::try_from(-129.25).unwrap().to_i8(), None, "out of range");
+}
+
+#[test]
+fn cutoff_bound() {
+    let scheme = CutoffScheme {
+        comparisons: Cutoff::INTEGER,
+        arithmetic: Cutoff {
+            fixed: Some(Index::Bit(-4)),
+            floating: None,
+            round: Rounding::Floor
+        }
+    };
+    let x = scheme.claim(BigFixed::try_from(1.5).unwrap()).unwrap();
+    let three = scheme.claim(BigFixed::from(3)).unwrap();
+    assert_eq!(x.to_f64(), Some(1.5), "to_f64");
+    assert_eq!(x.to_i32(), Some(1), "to_i32");
+    assert_eq!(scheme.claim(BigFixed::try_from(-1.5).unwrap()).unwrap().to_i32(), Some(-1), "negative to_i32");
+    assert_eq!(x.checked_add(&three).unwrap().value, BigFixed::try_from(4.5).unwrap(), "checked_add");
+    // every multiplication is cut off: 1.5^2 = 2.25, 2.25^2 = 5.0625 -> 5.0625 fits, times 1.5 = 7.59375 -> 7.5625
+    assert_eq!((&x).pow(5u32).value, BigFixed::try_from(7.5625).unwrap(), "pow");
+    assert_eq!(x.checked_div(&three).unwrap().value, BigFixed::try_from(0.5).unwrap(), "checked_div");
+    assert!(x.checked_div(&scheme.claim(BigFixed::from(0)).unwrap()).is_none(), "divide by zero");
+}
@@ -1,51 +1,51 @@
-use bigfixed::{digit::*, Index, BigFixed};
+use bigfixed::{digit::*, Index, Cutoff, Rounding, BigFixed, BigFixedError};
 
 #[test]
 fn add_digit() {
     let mut x = BigFixed {
         head: 0,
-        body: vec![],
+        body: vec![].into(),
         position: Index::Position(0)
     };
     x.add_digit(ALLONES, Index::Position(0)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![ALLONES],
+        body: vec![ALLONES].into(),
         position: Index::Position(0)
     }).unwrap(), "0 + 9");
     x.add_digit(1, Index::Position(-2)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![1, 0, ALLONES],
+        body: vec![1, 0, ALLONES].into(),
         position: Index::Position(-2)
     }).unwrap(), "9 + 0.01");
     x.add_digit(ALLONES, Index::Position(-1)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![1, ALLONES, ALLONES],
+        body: vec![1, ALLONES, ALLONES].into(),
         position: Index::Position(-2)
     }).unwrap(), "9.01 + 0.9");
     x.add_digit(ALLONES, Index::Position(-2)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![0, 0, 0, 1],
+        body: vec![0, 0, 0, 1].into(),
         position: Index::Position(-2)
     }).unwrap(), "9.91 + 0.09");
     x = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Index::Position(1)
     };
     x.add_digit(1, Index::Position(-1)).ok();
     assert!(x.full_eq(&BigFixed {
         head: ALLONES,
-        body: vec![1, 0],
+        body: vec![1, 0].into(),
         position: Index::Position(-1)
     }).unwrap(), "-10 + 0.1");
     x.add_digit(1, Index::Position(1)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![1, 0, 0],
+        body: vec![1, 0, 0].into(),
         position: Index::Position(-1)
     }).unwrap(), "-9.99 + 10");
 }
@@ -54,49 +54,49 @@ fn add_digit() {
 fn add_digit_drop_overflow() {
     let mut x = BigFixed {
         head: 0,
-        body: vec![],
+        body: vec![].into(),
         position: Index::Position(0)
     };
     x.add_digit_drop_overflow(ALLONES, Index::Position(0)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![],
+        body: vec![].into(),
         position: Index::Position(0)
     }).unwrap(), "0 + 9");
     x[0] = ALLONES; // the overflow which was dropped
     x.add_digit_drop_overflow(1, Index::Position(-2)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![1, 0, ALLONES],
+        body: vec![1, 0, ALLONES].into(),
         position: Index::Position(-2)
     }).unwrap(), "9 + 0.01");
     x.add_digit_drop_overflow(ALLONES, Index::Position(-1)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![1, ALLONES, ALLONES],
+        body: vec![1, ALLONES, ALLONES].into(),
         position: Index::Position(-2)
     }).unwrap(), "9.01 + 0.9");
     x.add_digit_drop_overflow(ALLONES, Index::Position(-2)).ok();
     assert!(x.full_eq(&BigFixed {
         head: 0,
-        body: vec![0, 0, 0],
+        body: vec![0, 0, 0].into(),
         position: Index::Position(-2)
     }).unwrap(), "9.91 + 0.09");
     x = BigFixed {
         head: ALLONES,
-        body: vec![],
+        body: vec![].into(),
         position: Index::Position(1)
     };
     x.add_digit_drop_overflow(1, Index::Position(-1)).ok();
     assert!(x.full_eq(&BigFixed {
         head: ALLONES,
-        body: vec![1, 0],
+        body: vec![1, 0].into(),
         position: Index::Position(-1)
     }).unwrap(), "-10 + 0.1");
     x.add_digit_drop_overflow(1, Index::Position(1)).ok();
     assert!(x.full_eq(&BigFixed {
         head: ALLONES,
-        body: vec![1, 0],
+        body: vec![1, 0].into(),
         position: Index::Position(-1)
     }).unwrap(), "-9.99 + 10");
 }
@@ -134,7 +134,7 @@ fn abs() {
 fn add() {
     let big = 0xE2103A85FD47AB2E94F2E5108CB5E24i128;
     for i in 0..=12 {
-        let a = (big >> (10*i + 4)) as i128;
+        let a = big >> (10*i + 4);
         for j in 0..3 {
             let b = (a << j) * if (j*i) / 2 == 0 {1} else {-1};
             assert_eq!(BigFixed::from(a + b), BigFixed::from(a) + BigFixed::from(b), "{} {}", i, j);
@@ -252,7 +252,7 @@ fn bitwise() {
 fn mul() {
     let big = 0xE2103A85FD47AB2i128;
     for i in 0..=5 {
-        let a = (big >> (10*i + 4)) as i128;
+        let a = big >> (10*i + 4);
         for j in 0..3 {
             let b = (a << j) * if (j*i) / 2 == 0 {1} else {-1};
             assert_eq!(BigFixed::from(a * b), BigFixed::from(a) * BigFixed::from(b), "{} {}", i, j);
@@ -272,3 +272,205 @@ fn div() {
     //let bottom = BigFixed::from(17).shift(Index::Position(-3));
 
 }
+
+#[test]
+fn exact_div_rem() {
+    let x = BigFixed::try_from(-2.75).unwrap();
+    let mut y = x.clone();
+    y.div_assign(&BigFixed::from(4)).unwrap();
+    assert_eq!(y, BigFixed::try_from(-0.6875).unwrap(), "exact quotient");
+    assert_eq!(y.clone().div_assign(&BigFixed::from(3)), Err(BigFixedError::NonTerminating), "non terminating");
+    assert_eq!(y.clone().div_assign(&BigFixed::ZERO), Err(BigFixedError::DivisionByZero), "divide by zero");
+    assert_eq!(x.checked_div(&BigFixed::from(2)), Some(BigFixed::try_from(-1.375).unwrap()), "checked_div");
+    assert_eq!(x.checked_div(&BigFixed::from(3)), None, "checked_div non terminating");
+    assert_eq!(x.checked_div(&BigFixed::ZERO), None, "checked_div by zero");
+    // the remainder takes the sign of the dividend like the primitive types
+    for (a, b) in [(7.5, 2.0), (-7.5, 2.0), (7.5, -2.0), (-7.5, -2.0), (0.75, 0.25), (-0.125, 3.0)] {
+        let mut r = BigFixed::try_from(a).unwrap();
+        r.rem_assign(&BigFixed::try_from(b).unwrap()).unwrap();
+        assert_eq!(r, BigFixed::try_from(a % b).unwrap(), "{} % {}", a, b);
+    }
+    assert_eq!(x.checked_rem(&BigFixed::from(1)), Some(BigFixed::try_from(-0.75).unwrap()), "checked_rem");
+    assert_eq!(x.clone().rem_assign(&BigFixed::ZERO), Err(BigFixedError::DivisionByZero), "rem by zero");
+    assert_eq!(x.checked_rem(&BigFixed::ZERO), None, "checked_rem by zero");
+}
+
+#[test]
+fn cutoff_ops() {
+    let mut seed: u64 = 3;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        seed >> 16
+    };
+    let mut values = vec![];
+    for _ in 0..16 {
+        let len = (next() % 12) as usize;
+        // runs of ALLONES and zeros make long carries and values sitting on rounding boundaries
+        let body: Vec<Digit> = (0..len).map(|_| match next() % 4 {
+            0 => ALLONES,
+            1 => 0,
+            _ => next() as Digit
+        }).collect();
+        let head = if next() % 2 == 0 {0} else {ALLONES};
+        values.push(BigFixed::construct(head, body, Index::Position((next() % 10) as isize - 6)).unwrap());
+    }
+    values.push(BigFixed::from(1).shift(Index::Bit(-37)).unwrap());
+    values.push(BigFixed::from(-1).shift(Index::Bit(-37)).unwrap());
+    values.push(BigFixed::construct(0, vec![0x8000, 0, 0, 1], Index::Position(-4)).unwrap());
+    values.push(BigFixed::construct(ALLONES, vec![0x8000, 0, 0, ALLONES], Index::Position(-4)).unwrap());
+    let cutoffs = [
+        (Some(Index::Bit(-40)), None),
+        (Some(Index::Position(-3)), None),
+        (Some(Index::Bit(5)), None),
+        (None, Some(Index::Bit(30))),
+        (None, Some(Index::Position(3))),
+        (Some(Index::Bit(-20)), Some(Index::Bit(50)))
+    ];
+    let roundings = [Rounding::Floor, Rounding::Ceiling, Rounding::Round, Rounding::TowardsZero, Rounding::AwayFromZero];
+    for x in values.iter() {
+        for y in values.iter() {
+            for &(fixed, floating) in cutoffs.iter() {
+                for &round in roundings.iter() {
+                    let cutoff = Cutoff {fixed, floating, round};
+                    let cut = |mut z: BigFixed| {
+                        z.cutoff(cutoff).unwrap();
+                        z
+                    };
+                    // identical down to the representation
+                    assert!(x.add_c(y, cutoff).unwrap().structurally_eq(&cut(x.clone() + y.clone())), "{} + {} {:?}", x, y, cutoff);
+                    assert!(x.sub_c(y, cutoff).unwrap().structurally_eq(&cut(x.clone() - y.clone())), "{} - {} {:?}", x, y, cutoff);
+                    assert!(x.mul_c(y, cutoff).unwrap().structurally_eq(&cut(x.clone() * y.clone())), "{} * {} {:?}", x, y, cutoff);
+                    let mut product = x.clone();
+                    product.mul_assign_c(y, cutoff).unwrap();
+                    assert!(product.structurally_eq(&cut(x.clone() * y.clone())), "{} *= {} {:?}", x, y, cutoff);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn mul_add() {
+    let cutoff = Cutoff {
+        fixed: Some(Index::Bit(-2)),
+        floating: None,
+        round: Rounding::Floor
+    };
+    // 1.25 * 1.25 = 1.5625 floors to 1.5 on its own, and 1.5 + 0.1875 floors to 1.5 again; fused, 1.75 is exact
+    let x = BigFixed::try_from(1.25).unwrap();
+    let b = BigFixed::try_from(0.1875).unwrap();
+    assert_eq!(x.mul_add_c(&x, &b, cutoff).unwrap(), BigFixed::try_from(1.75).unwrap(), "fused");
+    assert_eq!(x.mul_c(&x, cutoff).unwrap().add_c(&b, cutoff).unwrap(), BigFixed::try_from(1.5).unwrap(), "double rounding");
+
+    let mut seed: u64 = 29;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        BigFixed::from((seed >> 8) as i64 >> (seed % 56)).shift(Index::Bit((seed % 64) as isize - 32)).unwrap()
+    };
+    for _ in 0..100 {
+        let (x, a, b) = (next(), next(), next());
+        for round in [Rounding::Floor, Rounding::Ceiling, Rounding::Round, Rounding::TowardsZero, Rounding::AwayFromZero] {
+            let cutoff = Cutoff {
+                fixed: Some(Index::Bit(-10)),
+                floating: Some(Index::Bit(40)),
+                round
+            };
+            let mut exact = x.clone() * a.clone() + b.clone();
+            exact.cutoff(cutoff).unwrap();
+            assert_eq!(x.mul_add_c(&a, &b, cutoff).unwrap(), exact, "{} * {} + {}", x, a, b);
+            let mut y = x.clone();
+            y.mul_add_assign_c(&a, &b, cutoff).unwrap();
+            assert_eq!(y, exact, "assign {} * {} + {}", x, a, b);
+        }
+    }
+}
+
+#[test]
+fn cutoff_comparisons() {
+    let mut seed: u64 = 8;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        seed >> 16
+    };
+    let mut values = vec![BigFixed::from(0), BigFixed::from(-1), BigFixed::from(1).shift(Index::Bit(-3)).unwrap()];
+    for _ in 0..24 {
+        let len = (next() % 5) as usize;
+        let body: Vec<Digit> = (0..len).map(|_| match next() % 3 {
+            0 => ALLONES,
+            1 => 0,
+            _ => next() as Digit
+        }).collect();
+        let head = if next() % 2 == 0 {0} else {ALLONES};
+        values.push(BigFixed::construct(head, body, Index::Position((next() % 4) as isize - 2)).unwrap());
+    }
+    // an unformatted value with a Bit position
+    values.push(BigFixed {
+        head: ALLONES,
+        body: vec![0x1234, 0].into(),
+        position: Index::Bit(-5)
+    });
+    let cutoffs = [
+        (None, None),
+        (Some(Index::Bit(-9)), None),
+        (Some(Index::Position(1)), None),
+        (None, Some(Index::Bit(7))),
+        (Some(Index::Bit(-3)), Some(Index::Bit(20)))
+    ];
+    let roundings = [Rounding::Floor, Rounding::Ceiling, Rounding::Round, Rounding::TowardsZero, Rounding::AwayFromZero];
+    for &(fixed, floating) in cutoffs.iter() {
+        for &round in roundings.iter() {
+            let cutoff = Cutoff {fixed, floating, round};
+            let cut = |x: &BigFixed| {
+                let mut z = x.clone();
+                z.cutoff(cutoff).unwrap();
+                z
+            };
+            for x in values.iter() {
+                assert_eq!(x.is_zero_c(cutoff).unwrap(), cut(x).is_zero(), "is_zero {} {:?}", x, cutoff);
+                assert_eq!(x.greatest_bit_position_c(cutoff).unwrap(), cut(x).greatest_bit_position().unwrap(), "greatest bit {} {:?}", x, cutoff);
+                for &(fixed_c, floating_c) in cutoffs.iter() {
+                    let cutoff_c = Cutoff {fixed: fixed_c, floating: floating_c, round};
+                    let index = x.cutoff_index_c(cutoff_c, cutoff).unwrap();
+                    let expected = cut(x).cutoff_index(cutoff_c).unwrap();
+                    assert!(index == expected && index.bit_value() == expected.bit_value(), "cutoff index {} {:?} {:?}", x, cutoff_c, cutoff);
+                }
+                for y in values.iter() {
+                    assert_eq!(x.full_eq_c(y, cutoff).unwrap(), cut(x).full_eq(&cut(y)).unwrap(), "{} == {} {:?}", x, y, cutoff);
+                    assert_eq!(x.partial_cmp_c(y, cutoff), cut(x).partial_cmp(&cut(y)), "{} cmp {} {:?}", x, y, cutoff);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn add_sub_in_place() {
+    let mut seed: u64 = 21;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        seed >> 16
+    };
+    for _ in 0..300 {
+        let mut values = vec![];
+        for _ in 0..2 {
+            let body: Vec<Digit> = (0..next() % 6).map(|_| if next() % 3 == 0 {ALLONES} else {next() as Digit}).collect();
+            let head = if next() % 2 == 0 {0} else {ALLONES};
+            values.push(BigFixed::construct(head, body, Index::Position((next() % 6) as isize - 3)).unwrap());
+        }
+        let (x, y) = (&values[0], &values[1]);
+        // subtraction agrees with negating around an addition, and undoes addition
+        let mut expected = (-x).unwrap();
+        expected.add_assign(y).unwrap();
+        expected.negate().unwrap();
+        assert_eq!(x.clone() - y.clone(), expected, "{} - {}", x, y);
+        assert_eq!(x.clone() + y.clone() - y.clone(), x.clone(), "{} + {} - {}", x, y, y);
+        assert_eq!(x.clone() - y.clone() + y.clone(), x.clone(), "{} - {} + {}", x, y, y);
+    }
+    // no reallocation when the body already has room
+    let mut x = BigFixed::construct(0, vec![1, 2, 3, 4, 5, 6, 7, 8, 9], Index::Position(0)).unwrap();
+    x.body.reserve(32);
+    let body = x.body.as_ptr();
+    x.add_assign(&BigFixed::construct(ALLONES, vec![5, 6], Index::Position(-3)).unwrap()).unwrap();
+    x.sub_assign(&BigFixed::construct(0, vec![ALLONES, 1], Index::Position(4)).unwrap()).unwrap();
+    assert_eq!(x.body.as_ptr(), body, "reallocated");
+}
@@ -0,0 +1,94 @@
+use bigfixed::{Index, Cutoff, Rounding, BigFixed, BigFixedError, Polynomial, RationalFunction};
+
+fn poly(coefficients: &[f64]) -> Polynomial {
+    Polynomial::new(coefficients.iter().map(|&c| BigFixed::try_from(c).unwrap()).collect())
+}
+
+fn cutoff(bit: isize, round: Rounding) -> Cutoff {
+    Cutoff {
+        fixed: Some(Index::Bit(bit)),
+        floating: None,
+        round
+    }
+}
+
+#[test]
+fn arithmetic() {
+    // 1 - 2x + 0.5x^2
+    let p = poly(&[1.0, -2.0, 0.5]);
+    // 3 + x
+    let q = poly(&[3.0, 1.0]);
+    assert_eq!(p.degree(), Some(2), "degree");
+    assert_eq!(poly(&[1.0, 0.0, 0.0]), poly(&[1.0]), "trailing zeros trimmed");
+    assert_eq!(Polynomial::zero().degree(), None, "zero degree");
+    assert_eq!(&p + &q, poly(&[4.0, -1.0, 0.5]), "add");
+    assert_eq!(&p - &p, Polynomial::zero(), "sub cancels");
+    assert_eq!((&p - &poly(&[0.0, 0.0, 0.5])).degree(), Some(1), "sub lowers degree");
+    assert_eq!(&p * &q, poly(&[3.0, -5.0, -0.5, 0.5]), "mul");
+    assert_eq!(&p * &Polynomial::zero(), Polynomial::zero(), "mul by zero");
+    assert_eq!(p.derivative().unwrap(), poly(&[-2.0, 1.0]), "derivative");
+    assert_eq!(poly(&[7.0]).derivative().unwrap(), Polynomial::zero(), "derivative of a constant");
+    // p(q(x)) = 1 - 2(3 + x) + 0.5(3 + x)^2 = -0.5 + x + 0.5x^2
+    assert_eq!(p.compose(&q).unwrap(), poly(&[-0.5, 1.0, 0.5]), "compose");
+
+    let mut seed: u64 = 41;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        BigFixed::from((seed >> 40) as i64 - (1 << 23)).shift(Index::Bit((seed % 16) as isize - 8)).unwrap()
+    };
+    for _ in 0..20 {
+        let p = Polynomial::new((0..4).map(|_| next()).collect());
+        let q = Polynomial::new((0..3).map(|_| next()).collect());
+        let x = next();
+        let (px, qx) = (p.eval(&x).unwrap(), q.eval(&x).unwrap());
+        assert_eq!((&p + &q).eval(&x).unwrap(), &px + &qx, "add at {}", x);
+        assert_eq!((&p * &q).eval(&x).unwrap(), &px * &qx, "mul at {}", x);
+        assert_eq!(p.compose(&q).unwrap().eval(&x).unwrap(), p.eval(&qx).unwrap(), "compose at {}", x);
+    }
+}
+
+#[test]
+fn evaluation() {
+    let p = poly(&[1.0, -2.0, 0.5]);
+    let x = BigFixed::try_from(1.5).unwrap();
+    assert_eq!(p.eval(&x).unwrap(), BigFixed::try_from(-0.875).unwrap(), "exact");
+    assert_eq!(Polynomial::zero().eval_c(&x, Cutoff::INTEGER).unwrap(), BigFixed::from(0), "zero");
+    // eval_c is Horner with a single rounding per step
+    let x = BigFixed::try_from(1.0 / 3.0).unwrap();
+    for round in [Rounding::Floor, Rounding::Ceiling, Rounding::Round, Rounding::TowardsZero, Rounding::AwayFromZero] {
+        let c = cutoff(-20, round);
+        let coefficients = p.coefficients();
+        let mut horner = coefficients[2].clone();
+        horner.cutoff(c).unwrap();
+        horner = horner.mul_add_c(&x, &coefficients[1], c).unwrap();
+        horner = horner.mul_add_c(&x, &coefficients[0], c).unwrap();
+        assert_eq!(p.eval_c(&x, c).unwrap(), horner, "{:?}", round);
+        let mut exact = p.eval(&x).unwrap();
+        exact.cutoff(c).unwrap();
+        let error = (p.eval_c(&x, c).unwrap() - exact).abs().unwrap();
+        assert!(error <= BigFixed::from(1).shift(Index::Bit(-18)).unwrap(), "error {} {:?}", error, round);
+    }
+}
+
+#[test]
+fn rational() {
+    // (1 + x) / (1 - x)
+    let r = RationalFunction::new(poly(&[1.0, 1.0]), poly(&[1.0, -1.0]));
+    let c = cutoff(-30, Rounding::Round);
+    assert_eq!(r.eval_c(&BigFixed::try_from(0.5).unwrap(), c).unwrap(), BigFixed::from(3), "exact quotient");
+    // 1.25 / 0.75 = 5 / 3
+    let expected = BigFixed::from_ratio_c(&BigFixed::from(5), &BigFixed::from(3), c).unwrap();
+    assert_eq!(r.eval_c(&BigFixed::try_from(0.25).unwrap(), c).unwrap(), expected, "rounded quotient");
+}
+
+#[test]
+fn rational_pole() {
+    // x / (4x^2 - 1) has poles at 1/2 and -1/2
+    let r = RationalFunction::new(poly(&[0.0, 1.0]), poly(&[-1.0, 0.0, 4.0]));
+    let c = cutoff(-30, Rounding::Round);
+    assert_eq!(r.eval_c(&BigFixed::try_from(0.5).unwrap(), c), Err(BigFixedError::DivisionByZero), "pole");
+    assert_eq!(r.eval_c(&BigFixed::try_from(-0.5).unwrap(), c), Err(BigFixedError::DivisionByZero), "negative pole");
+    // 0.25 / -0.75
+    let expected = BigFixed::from_ratio_c(&BigFixed::from(-1), &BigFixed::from(3), c).unwrap();
+    assert_eq!(r.eval_c(&BigFixed::try_from(0.25).unwrap(), c).unwrap(), expected, "off the pole");
+}
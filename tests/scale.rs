@@ -0,0 +1,91 @@
+#![cfg(feature = "codec")]
+
+use bigfixed::{Index, Cutoff, CutoffScheme, CutoffBoundBigFixed, Rounding, BigFixed, BoundedBigFixed, BoundedCutoffBoundBigFixed, MAX_PARSE_BITS};
+
+use codec::{Encode, Decode, MaxEncodedLen};
+
+#[test]
+fn encode_decode() {
+    let x = BigFixed::try_from(-2.75).unwrap();
+    assert_eq!(x.encode(), x.to_bytes().unwrap(), "canonical bytes");
+    assert_eq!(BigFixed::decode(&mut x.encode().as_slice()).unwrap(), x, "round trip");
+    let mut seed: u64 = 11;
+    for _ in 0..100 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let x = BigFixed::from(seed as i64).shift(Index::Bit((seed % 300) as isize - 150)).unwrap();
+        assert_eq!(BigFixed::decode(&mut x.encode().as_slice()).unwrap(), x, "{}", x);
+    }
+    // several values and indexes in a row
+    let encoded = (BigFixed::from(7), Index::Bit(-3), BigFixed::from(-9)).encode();
+    assert_eq!(<(BigFixed, Index, BigFixed)>::decode(&mut encoded.as_slice()).unwrap(), (BigFixed::from(7), Index::Bit(-3), BigFixed::from(-9)), "tuple");
+
+    // a claimed length far past the input is rejected before anything is allocated
+    let mut huge = vec![0, 0];
+    huge.extend([0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+    huge.push(1);
+    assert!(BigFixed::decode(&mut huge.as_slice()).is_err(), "oversized");
+    assert!(BigFixed::decode(&mut [0u8, 0, 2, 0, 1].as_slice()).is_err(), "non canonical");
+
+    let scheme = CutoffScheme {
+        comparisons: Cutoff::INTEGER,
+        arithmetic: Cutoff {
+            fixed: Some(Index::Bit(-4)),
+            floating: None,
+            round: Rounding::Ceiling
+        }
+    };
+    let bound = scheme.claim(BigFixed::try_from(5.3).unwrap()).unwrap();
+    let decoded = CutoffBoundBigFixed::decode(&mut bound.encode().as_slice()).unwrap();
+    assert_eq!(decoded.value, BigFixed::try_from(5.3125).unwrap(), "bound value");
+    assert_eq!(decoded.scheme.arithmetic, scheme.arithmetic, "bound scheme");
+    // values are cut off by their scheme on the way in
+    let mut raw = scheme.encode();
+    raw.extend(BigFixed::try_from(5.3).unwrap().encode());
+    assert_eq!(CutoffBoundBigFixed::decode(&mut raw.as_slice()).unwrap().value, BigFixed::try_from(5.3125).unwrap(), "claimed");
+}
+
+#[test]
+fn bounded() {
+    type Small = BoundedBigFixed<4>;
+    assert_eq!(Small::max_encoded_len(), 1 + 10 + 1 + 4, "max encoded len");
+    assert_eq!(BoundedBigFixed::<1000>::max_encoded_len(), 1 + 10 + 2 + 1000, "max encoded len with a long length");
+    let x = Small::new(BigFixed::from(-0x1234_5678)).unwrap();
+    let encoded = x.encode();
+    assert!(encoded.len() <= Small::max_encoded_len(), "within bound");
+    assert_eq!(Small::decode(&mut encoded.as_slice()).unwrap(), x, "round trip");
+    assert!(Small::new(BigFixed::from(0x1_0000_0000i64 + 1)).is_err(), "too large to construct");
+    // an unbounded encoding with a five byte body does not decode as the bounded type
+    let long = BigFixed::from(0x12_3456_789ai64).encode();
+    assert!(BigFixed::decode(&mut long.as_slice()).is_ok(), "unbounded");
+    assert!(Small::decode(&mut long.as_slice()).is_err(), "bounded");
+
+    // a short body far from the point is refused before anything is built, by the bounded types only
+    let far = BigFixed::from(1).shift(Index::Bit(1 << 43)).unwrap();
+    let encoded = far.encode();
+    assert_eq!(BigFixed::decode(&mut encoded.as_slice()).unwrap(), far, "unbounded far position");
+    assert!(Small::decode(&mut encoded.as_slice()).is_err(), "bounded far position");
+    assert!(Small::new(far).is_err(), "far position to construct");
+    assert!(Small::decode(&mut BigFixed::from(1).shift(Index::Bit(-(1 << 43))).unwrap().encode().as_slice()).is_err(), "far negative position");
+    let edge = BigFixed::from(-1).shift(Index::Bit(MAX_PARSE_BITS as isize - 8)).unwrap();
+    assert_eq!(Small::decode(&mut edge.encode().as_slice()).unwrap().get(), &edge, "highest byte at the limit");
+    let past = BigFixed::from(1).shift(Index::Bit(MAX_PARSE_BITS as isize)).unwrap();
+    assert!(Small::new(past).is_err(), "past the limit");
+    let low = BigFixed::from(1).shift(Index::Bit(-(MAX_PARSE_BITS as isize))).unwrap();
+    assert_eq!(Small::decode(&mut low.encode().as_slice()).unwrap().get(), &low, "lowest byte at the limit");
+    // only values whose byte position overflows isize cannot be encoded, and no bounded value is one of them
+    let unencodable = BigFixed::construct(0, vec![1], Index::Position(isize::MAX / 2 + 1)).unwrap();
+    assert!(unencodable.to_bytes().is_err(), "byte position overflows");
+    assert!(Small::new(unencodable).is_err(), "unencodable to construct");
+
+    let scheme = CutoffScheme {
+        comparisons: Cutoff::INTEGER,
+        arithmetic: Cutoff::INTEGER
+    };
+    type SmallBound = BoundedCutoffBoundBigFixed<4>;
+    assert_eq!(SmallBound::max_encoded_len(), CutoffScheme::max_encoded_len() + Small::max_encoded_len(), "bound max encoded len");
+    let bound = SmallBound::new(scheme.claim(BigFixed::from(1000)).unwrap()).unwrap();
+    let encoded = bound.encode();
+    assert!(encoded.len() <= SmallBound::max_encoded_len(), "bound within bound");
+    assert_eq!(SmallBound::decode(&mut encoded.as_slice()).unwrap().get().value, BigFixed::from(1000), "bound round trip");
+    assert!(SmallBound::new(scheme.claim(BigFixed::from(i64::MAX)).unwrap()).is_err(), "bound too large");
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "serde")]
+
+use bigfixed::{Index, Cutoff, CutoffScheme, CutoffBoundBigFixed, Rounding, BigFixed, MAX_PARSE_BITS};
+
+#[test]
+fn json() {
+    let x = BigFixed::try_from(-1234.5625).unwrap();
+    assert_eq!(serde_json::to_string(&x).unwrap(), "\"-1234.5625\"", "serialize");
+    assert_eq!(serde_json::from_str::<BigFixed>("\"-1234.5625\"").unwrap(), x, "deserialize");
+    assert_eq!(serde_json::from_str::<BigFixed>("\"-0x4d2.9\"").unwrap(), x, "hex");
+    assert!(serde_json::from_str::<BigFixed>("\"0.1\"").is_err(), "inexact");
+    assert!(serde_json::from_str::<BigFixed>("\"one\"").is_err(), "invalid");
+    let tiny = BigFixed::from(3).shift(Index::Bit(-200)).unwrap();
+    assert_eq!(serde_json::from_str::<BigFixed>(&serde_json::to_string(&tiny).unwrap()).unwrap(), tiny, "round trip");
+
+    // a config file declaring a cutoff scheme
+    let config = r#"{
+        "comparisons": {"fixed": {"Bit": -8}, "floating": null, "round": "Round"},
+        "arithmetic": {"fixed": {"Position": -2}, "floating": {"Bit": 64}, "round": "TowardsZero"}
+    }"#;
+    let scheme: CutoffScheme = serde_json::from_str(config).unwrap();
+    assert_eq!(scheme.comparisons, Cutoff {
+        fixed: Some(Index::Bit(-8)),
+        floating: None,
+        round: Rounding::Round
+    }, "comparisons");
+    assert_eq!(scheme.arithmetic, Cutoff {
+        fixed: Some(Index::Position(-2)),
+        floating: Some(Index::Bit(64)),
+        round: Rounding::TowardsZero
+    }, "arithmetic");
+
+    // bound values are cut off by their scheme on the way in
+    let bound: CutoffBoundBigFixed = serde_json::from_str(&format!(r#"{{"scheme": {}, "value": "0x1.0000000000000000000000001"}}"#, config)).unwrap();
+    assert_eq!(bound.value, BigFixed::from(1), "claimed");
+    let encoded = serde_json::to_string(&bound).unwrap();
+    let decoded: CutoffBoundBigFixed = serde_json::from_str(&encoded).unwrap();
+    assert_eq!(decoded.value, bound.value, "bound round trip");
+}
+
+#[test]
+fn binary() {
+    let x = BigFixed::try_from(-2.75).unwrap();
+    let encoded = bincode::serialize(&x).unwrap();
+    // bincode prefixes the canonical bytes with their u64 length
+    assert_eq!(&encoded[8..], x.to_bytes().unwrap().as_slice(), "canonical bytes");
+    assert_eq!(bincode::deserialize::<BigFixed>(&encoded).unwrap(), x, "round trip");
+    let mut seed: u64 = 7;
+    for _ in 0..100 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let x = BigFixed::from(seed as i64).shift(Index::Bit((seed % 300) as isize - 150)).unwrap();
+        assert_eq!(bincode::deserialize::<BigFixed>(&bincode::serialize(&x).unwrap()).unwrap(), x, "{}", x);
+    }
+    // non canonical bytes are rejected
+    let mut bad = bincode::serialize(&BigFixed::from(1)).unwrap();
+    bad.push(0);
+    bad[0] += 1;
+    assert!(bincode::deserialize::<BigFixed>(&bad).is_err(), "non canonical");
+
+    let scheme = CutoffScheme {
+        comparisons: Cutoff::INTEGER,
+        arithmetic: Cutoff {
+            fixed: Some(Index::Bit(-4)),
+            floating: None,
+            round: Rounding::Floor
+        }
+    };
+    let bound = scheme.claim(BigFixed::try_from(5.3).unwrap()).unwrap();
+    let decoded: CutoffBoundBigFixed = bincode::deserialize(&bincode::serialize(&bound).unwrap()).unwrap();
+    assert_eq!(decoded.value, BigFixed::try_from(5.25).unwrap(), "bound value");
+    assert_eq!(decoded.scheme.arithmetic, scheme.arithmetic, "bound scheme");
+}
+
+#[test]
+fn limits() {
+    // short strings which would take forever or exhaust memory to build
+    assert!(serde_json::from_str::<BigFixed>("\"1e999999999\"").is_err(), "decimal exponent");
+    assert!(serde_json::from_str::<BigFixed>("\"0x1p999999999999\"").is_err(), "binary exponent");
+    assert!(serde_json::from_str::<BigFixed>("\"0x1p-999999999999\"").is_err(), "negative binary exponent");
+    assert!(serde_json::from_str::<BigFixed>(&format!("\"{}\"", "9".repeat(MAX_PARSE_BITS + 1))).is_err(), "long string");
+    let edge = BigFixed::from(1).shift(Index::Bit(-(MAX_PARSE_BITS as isize))).unwrap();
+    assert_eq!(serde_json::from_str::<BigFixed>(&format!("\"0x1p-{}\"", MAX_PARSE_BITS)).unwrap(), edge, "lowest bit at the limit");
+    assert!(serde_json::from_str::<BigFixed>(&format!("\"0x1p-{}\"", MAX_PARSE_BITS + 1)).is_err(), "lowest bit past the limit");
+    assert!(serde_json::from_str::<BigFixed>(&format!("\"0x1p{}\"", MAX_PARSE_BITS)).is_err(), "highest bit past the limit");
+    assert_eq!(serde_json::from_str::<BigFixed>(&format!("\"-0x1p{}\"", MAX_PARSE_BITS - 1)).unwrap(), BigFixed::from(-1).shift(Index::Bit(MAX_PARSE_BITS as isize - 1)).unwrap(), "highest bit at the limit");
+
+    // binary input is held to the same limits
+    let far = BigFixed::from(1).shift(Index::Bit(1 << 40)).unwrap();
+    assert!(bincode::deserialize::<BigFixed>(&bincode::serialize(&far).unwrap()).is_err(), "far position");
+    assert_eq!(bincode::deserialize::<BigFixed>(&bincode::serialize(&edge).unwrap()).unwrap(), edge, "position at the limit");
+}
@@ -0,0 +1,105 @@
+use bigfixed::{Index, Cutoff, CutoffScheme, CutoffBoundBigFixed, Rounding, BigFixed, BigFixedError};
+
+fn random(seed: &mut u64) -> BigFixed {
+    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    let x = BigFixed::from((*seed >> 8) as i64 >> (*seed % 56));
+    x.shift(Index::Bit((*seed % 64) as isize - 32)).unwrap()
+}
+
+fn cutoff(bit: isize, round: Rounding) -> Cutoff {
+    Cutoff {
+        fixed: Some(Index::Bit(bit)),
+        floating: None,
+        round
+    }
+}
+
+#[test]
+fn sums_and_products() {
+    let values: Vec<BigFixed> = [1.5, -2.25, 0.125, 1024.0].iter().map(|&x| BigFixed::try_from(x).unwrap()).collect();
+    assert_eq!(values.iter().sum::<BigFixed>(), BigFixed::try_from(1023.375).unwrap(), "borrowed sum");
+    assert_eq!(values.clone().into_iter().sum::<BigFixed>(), BigFixed::try_from(1023.375).unwrap(), "owned sum");
+    assert_eq!(values.iter().product::<BigFixed>(), BigFixed::try_from(-432.0).unwrap(), "borrowed product");
+    assert_eq!(values.clone().into_iter().product::<BigFixed>(), BigFixed::try_from(-432.0).unwrap(), "owned product");
+    assert_eq!(Vec::<BigFixed>::new().iter().sum::<BigFixed>(), BigFixed::from(0), "empty sum");
+    assert_eq!(Vec::<BigFixed>::new().iter().product::<BigFixed>(), BigFixed::from(1), "empty product");
+
+    // bound values are combined exactly and rounded once
+    let scheme = CutoffScheme {
+        comparisons: Cutoff::INTEGER,
+        arithmetic: Cutoff::INTEGER
+    };
+    let halves: Vec<CutoffBoundBigFixed> = (0..3).map(|_| CutoffBoundBigFixed {
+        scheme,
+        value: BigFixed::try_from(1.5).unwrap()
+    }).collect();
+    assert_eq!(CutoffBoundBigFixed::sum_s(&halves, scheme).unwrap().value, BigFixed::from(4), "bound sum");
+    assert_eq!(CutoffBoundBigFixed::product_s(halves.iter(), scheme).unwrap().value, BigFixed::from(3), "bound product");
+    let fine = CutoffScheme {
+        comparisons: Cutoff::INTEGER,
+        arithmetic: cutoff(-1, Rounding::Floor)
+    };
+    let sum = CutoffBoundBigFixed::sum_s(halves.clone(), fine).unwrap();
+    assert_eq!(sum.value, BigFixed::try_from(4.5).unwrap(), "owned sum");
+    assert_eq!(sum.scheme.arithmetic, fine.arithmetic, "claimed by the given scheme");
+
+    // Sum and Product take the scheme of the first value
+    let first = CutoffBoundBigFixed {
+        scheme: fine,
+        value: BigFixed::try_from(1.5).unwrap()
+    };
+    let mixed = [first, halves[0].clone(), halves[1].clone()];
+    let sum: Result<CutoffBoundBigFixed, BigFixedError> = mixed.iter().sum();
+    assert_eq!(sum.as_ref().unwrap().value, BigFixed::try_from(4.5).unwrap(), "borrowed Sum");
+    assert_eq!(sum.unwrap().scheme.arithmetic, fine.arithmetic, "first scheme");
+    assert_eq!(mixed.clone().into_iter().sum::<Result<CutoffBoundBigFixed, BigFixedError>>().unwrap().value, BigFixed::try_from(4.5).unwrap(), "owned Sum");
+    assert_eq!(mixed.iter().product::<Result<CutoffBoundBigFixed, BigFixedError>>().unwrap().value, BigFixed::try_from(3.0).unwrap(), "borrowed Product");
+    assert_eq!(halves.into_iter().product::<Result<CutoffBoundBigFixed, BigFixedError>>().unwrap().value, BigFixed::from(3), "owned Product");
+}
+
+#[test]
+fn empty_bound() {
+    let scheme = CutoffScheme {
+        comparisons: Cutoff::INTEGER,
+        arithmetic: Cutoff::INTEGER
+    };
+    let empty: Vec<CutoffBoundBigFixed> = vec![];
+    let sum = CutoffBoundBigFixed::sum_s(&empty, scheme).unwrap();
+    assert!(sum.value.is_zero(), "empty sum");
+    assert_eq!(sum.scheme.arithmetic, Cutoff::INTEGER, "empty sum scheme");
+    assert_eq!(CutoffBoundBigFixed::product_s(&empty, scheme).unwrap().value, BigFixed::from(1), "empty product");
+    assert_eq!(empty.iter().sum::<Result<CutoffBoundBigFixed, BigFixedError>>(), Err(BigFixedError::EmptyIterator), "empty Sum");
+    assert_eq!(empty.into_iter().product::<Result<CutoffBoundBigFixed, BigFixedError>>(), Err(BigFixedError::EmptyIterator), "empty Product");
+}
+
+#[test]
+fn single_rounding() {
+    // per step flooring loses a half at every step
+    let halves = vec![BigFixed::try_from(0.5).unwrap(); 4];
+    assert_eq!(BigFixed::sum_c(&halves, Cutoff::INTEGER).unwrap(), BigFixed::from(2), "sum_c");
+    let stepwise = halves.iter().fold(BigFixed::from(0), |total, x| &total + (x, Cutoff::INTEGER));
+    assert_eq!(stepwise, BigFixed::from(0), "stepwise");
+    // cancellation
+    let big = BigFixed::from(1i64 << 40);
+    let tiny = BigFixed::try_from(0.75).unwrap();
+    let values = [big.clone(), tiny.clone(), (-big.clone()).unwrap()];
+    assert_eq!(BigFixed::sum_c(values.iter(), cutoff(-1, Rounding::Round)).unwrap(), BigFixed::from(1), "cancellation");
+    assert_eq!(BigFixed::dot_c(&[big.clone(), tiny.clone(), big.clone()], &[big.clone(), tiny.clone(), (-big).unwrap()], cutoff(-2, Rounding::Floor)).unwrap(), BigFixed::try_from(0.5).unwrap(), "dot cancellation");
+
+    let mut seed = 23;
+    for _ in 0..50 {
+        let a: Vec<BigFixed> = (0..6).map(|_| random(&mut seed)).collect();
+        let b: Vec<BigFixed> = (0..6).map(|_| random(&mut seed)).collect();
+        for round in [Rounding::Floor, Rounding::Ceiling, Rounding::Round, Rounding::TowardsZero, Rounding::AwayFromZero] {
+            for bit in [-20, 0, 17] {
+                let c = cutoff(bit, round);
+                let mut sum = a.iter().sum::<BigFixed>();
+                sum.cutoff(c).unwrap();
+                assert_eq!(BigFixed::sum_c(&a, c).unwrap(), sum, "sum_c");
+                let mut dot = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<BigFixed>();
+                dot.cutoff(c).unwrap();
+                assert_eq!(BigFixed::dot_c(&a, &b, c).unwrap(), dot, "dot_c");
+            }
+        }
+    }
+}